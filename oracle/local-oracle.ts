@@ -0,0 +1,77 @@
+// Reference oracle for local testing. It polls for unfulfilled
+// RandomnessRequest accounts and fulfils them with an ed25519 signature over
+// (request key, seed), signed by a key registered through `add_oracle`.
+// The signature isn't a unique proof, whoever holds an oracle key can choose
+// among spin results, so only register keys that are trusted with the outcome.
+//
+//   ORACLE_KEYPAIR=~/.config/solana/oracle.json yarn oracle
+
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Ed25519Program, Keypair, SYSVAR_INSTRUCTIONS_PUBKEY, Transaction } from "@solana/web3.js";
+import nacl from "tweetnacl";
+import fs from "fs";
+import { Spin } from "../target/types/spin";
+
+const POLL_INTERVAL_MS = 2000;
+
+function loadKeypair(path: string): Keypair {
+  const secret = JSON.parse(fs.readFileSync(path, "utf-8"));
+  return Keypair.fromSecretKey(Uint8Array.from(secret));
+}
+
+async function fulfill(program: Program<Spin>, oracle: Keypair, pool: anchor.web3.PublicKey, request: anchor.web3.PublicKey, seed: number[]) {
+  const message = Buffer.concat([request.toBuffer(), Buffer.from(seed)]);
+  const signature = nacl.sign.detached(message, oracle.secretKey);
+
+  const tx = new Transaction();
+  tx.add(
+    Ed25519Program.createInstructionWithPublicKey({
+      publicKey: oracle.publicKey.toBytes(),
+      message,
+      signature,
+    }),
+    await program.methods
+      .fulfillRandomness(Array.from(signature))
+      .accounts({
+        oracle: oracle.publicKey,
+        pool,
+        randomnessRequest: request,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .instruction()
+  );
+
+  return await program.provider.sendAndConfirm(tx, [oracle]);
+}
+
+async function main() {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Spin as Program<Spin>;
+  const oracle = loadKeypair(process.env.ORACLE_KEYPAIR);
+
+  console.log("oracle", oracle.publicKey.toBase58(), "watching", program.programId.toBase58());
+
+  for (;;) {
+    const requests = await program.account.randomnessRequest.all();
+    for (const { publicKey, account } of requests) {
+      if (account.isFulfilled) {
+        continue;
+      }
+      try {
+        const sig = await fulfill(program, oracle, account.pool, publicKey, account.seed);
+        console.log("fulfilled", publicKey.toBase58(), sig);
+      } catch (e) {
+        console.error("failed to fulfil", publicKey.toBase58(), e);
+      }
+    }
+    await new Promise((resolve) => setTimeout(resolve, POLL_INTERVAL_MS));
+  }
+}
+
+main().catch((e) => {
+  console.error(e);
+  process.exit(1);
+});
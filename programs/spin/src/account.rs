@@ -1,22 +1,234 @@
 use anchor_lang::prelude::*;
+use solana_program::hash;
 
 use crate::constants::*;
 use crate::errors::*;
+use crate::utils::*;
 
 
 #[account]
 #[derive(Default)]
 pub struct Pool {
+    pub wheel_id: u64,
     pub superadmin: Pubkey,
-    pub dev_wallet: Pubkey,
-    pub dust_mint: Pubkey,
-    pub forge_mint: Pubkey,
+    // proposed superadmin, takes over once it signs accept_superadmin
+    pub pending_superadmin: Pubkey,
     pub sol_price: u64,
-    pub dust_price: u64,
-    pub forge_price: u64,
-    pub dev_fee: u64,
+    // fee on SOL payments, in bps
+    pub sol_fee_bps: u64,
+
+    // fees are split among these recipients by bps share; unassigned shares stay in the vault
+    pub split_recipient_list: [Pubkey; MAX_SPLIT_RECIPIENT_COUNT],
+    pub split_share_list: [u64; MAX_SPLIT_RECIPIENT_COUNT],
+    pub split_count: u8,
+
+    // active spin item weights must add up to this
+    pub ratio_total: u64,
+    // ceiling on the wheel's expected payout per pay mode, in bps of the price
+    pub max_rtp_bps: u64,
+
+    // keys allowed to fulfil RandomnessRequest accounts
+    pub oracle_list: [Pubkey; MAX_ORACLE_COUNT],
+    pub oracle_count: u8,
+
+    // seconds a staged wheel config waits before it can be activated, 0 applies changes directly
+    pub config_delay: i64,
+
+    // PAUSE_* bits, set by pausers
+    pub pause_flags: u8,
+
+    // share of every spin payment that goes into the jackpot, in bps
+    pub jackpot_bps: u64,
+
+    // bulk spins of at least discount_min_count get discount_bps off the total
+    pub discount_min_count_list: [u8; MAX_DISCOUNT_TIER_COUNT],
+    pub discount_bps_list: [u64; MAX_DISCOUNT_TIER_COUNT],
+    pub discount_tier_count: u8,
+
+    // seconds between a user's free spins, 0 disables them
+    pub free_spin_period: i64,
 }
 
+// Checks bulk discount tiers and pads them to the fixed-size lists kept on chain.
+fn discount_tiers(
+    min_count_list: &[u8],
+    discount_bps_list: &[u64],
+) -> Result<([u8; MAX_DISCOUNT_TIER_COUNT], [u64; MAX_DISCOUNT_TIER_COUNT], u8)> {
+    require!(min_count_list.len() == discount_bps_list.len(), SpinError::InvalidBulkDiscount);
+    require!(min_count_list.len() <= MAX_DISCOUNT_TIER_COUNT, SpinError::InvalidBulkDiscount);
+    for (min_count, discount_bps) in min_count_list.iter().zip(discount_bps_list.iter()) {
+        require!(*min_count >= 2 && *min_count <= MAX_SPIN_COUNT, SpinError::InvalidBulkDiscount);
+        require!(*discount_bps < BPS_DENOMINATOR, SpinError::InvalidBulkDiscount);
+    }
+
+    let mut padded_min_count_list = [0; MAX_DISCOUNT_TIER_COUNT];
+    let mut padded_discount_bps_list = [0; MAX_DISCOUNT_TIER_COUNT];
+    padded_min_count_list[..min_count_list.len()].copy_from_slice(min_count_list);
+    padded_discount_bps_list[..discount_bps_list.len()].copy_from_slice(discount_bps_list);
+
+    Ok((padded_min_count_list, padded_discount_bps_list, min_count_list.len() as u8))
+}
+
+impl Pool {
+    // The outgoing superadmin keeps no implicit role, only what its own AdminInfo
+    // entry grants; to stay an admin it adds itself one before the transfer.
+    pub fn accept_superadmin(&mut self, admin_info: &mut AdminInfo, new_superadmin: Pubkey) -> Result<()> {
+        require!(
+            self.pending_superadmin != Pubkey::default() && self.pending_superadmin.eq(&new_superadmin),
+            SpinError::IncorrectSuperAdminOrAdmin
+        );
+
+        // the superadmin holds every role, so a leftover admin entry would outlive a later transfer
+        if admin_info.is_admin(&new_superadmin) {
+            admin_info.delete_admin(new_superadmin)?;
+        }
+        self.superadmin = new_superadmin;
+        self.pending_superadmin = Pubkey::default();
+
+        Ok(())
+    }
+
+    pub fn set_bulk_discounts(&mut self, min_count_list: &[u8], discount_bps_list: &[u64]) -> Result<()> {
+        (self.discount_min_count_list, self.discount_bps_list, self.discount_tier_count) =
+            discount_tiers(min_count_list, discount_bps_list)?;
+
+        Ok(())
+    }
+
+    pub fn max_discount_bps(&self) -> u64 {
+        self.discount_bps_list[..self.discount_tier_count as usize].iter().copied().max().unwrap_or(0)
+    }
+
+    // Total for `spin_count` spins at `price`, after the best matching discount tier.
+    pub fn bulk_price(&self, price: u64, spin_count: u8) -> Result<u64> {
+        require!((1..=MAX_SPIN_COUNT).contains(&spin_count), SpinError::InvalidSpinCount);
+
+        let total = price.checked_mul(spin_count as u64).ok_or(SpinError::AmountOverflow)?;
+        let discount_bps = (0..self.discount_tier_count as usize)
+            .filter(|i| self.discount_min_count_list[*i] <= spin_count)
+            .map(|i| self.discount_bps_list[i])
+            .max()
+            .unwrap_or(0);
+
+        Ok(total - dev_fee(total, discount_bps)?)
+    }
+
+    pub fn require_unpaused(&self, flag: u8) -> Result<()> {
+        if self.pause_flags & flag == 0 {
+            return Ok(());
+        }
+
+        match flag {
+            PAUSE_SOL_SPIN => err!(SpinError::SolSpinPaused),
+            PAUSE_TOKEN_SPIN => err!(SpinError::TokenSpinPaused),
+            PAUSE_CLAIM => err!(SpinError::ClaimPaused),
+            PAUSE_TICKET_SPIN => err!(SpinError::TicketSpinPaused),
+            PAUSE_FREE_SPIN => err!(SpinError::FreeSpinPaused),
+            _ => err!(SpinError::WithdrawPaused),
+        }
+    }
+
+    // Leaves the wheel to its AdminInfo entries, which are kept as they are.
+    pub fn renounce_superadmin(&mut self) {
+        self.superadmin = Pubkey::default();
+        self.pending_superadmin = Pubkey::default();
+    }
+
+    pub fn set_revenue_split(&mut self, recipient_list: &[Pubkey], share_list: &[u64]) -> Result<()> {
+        require!(recipient_list.len() == share_list.len(), SpinError::InvalidRevenueSplit);
+        require!(recipient_list.len() <= MAX_SPLIT_RECIPIENT_COUNT, SpinError::CountOverflowAddItem);
+        require!(share_list.iter().sum::<u64>() <= BPS_DENOMINATOR, SpinError::InvalidRevenueSplit);
+
+        self.split_recipient_list = [Pubkey::default(); MAX_SPLIT_RECIPIENT_COUNT];
+        self.split_share_list = [0; MAX_SPLIT_RECIPIENT_COUNT];
+        for (i, recipient) in recipient_list.iter().enumerate() {
+            require!(*recipient != Pubkey::default(), SpinError::InvalidRevenueSplit);
+            self.split_recipient_list[i] = *recipient;
+            self.split_share_list[i] = share_list[i];
+        }
+        self.split_count = recipient_list.len() as u8;
+
+        Ok(())
+    }
+
+    pub fn is_oracle(&self, oracle: &Pubkey) -> bool {
+        self.oracle_list[..self.oracle_count as usize].contains(oracle)
+    }
+
+    pub fn add_oracle(&mut self, oracle: Pubkey) -> Result<()> {
+        require!((self.oracle_count as usize) < MAX_ORACLE_COUNT, SpinError::OracleListFull);
+        require!(!self.is_oracle(&oracle), SpinError::OracleAlreadyExists);
+
+        self.oracle_list[self.oracle_count as usize] = oracle;
+        self.oracle_count += 1;
+
+        Ok(())
+    }
+
+    pub fn delete_oracle(&mut self, oracle: Pubkey) -> Result<()> {
+        for i in 0..self.oracle_count {
+            if self.oracle_list[i as usize].eq(&oracle) {
+                self.oracle_list[i as usize] = self.oracle_list[self.oracle_count as usize - 1];
+                self.oracle_list[self.oracle_count as usize - 1] = Pubkey::default();
+                self.oracle_count -= 1;
+                return Ok(());
+            }
+        }
+
+        err!(SpinError::OracleNotFound)
+    }
+}
+
+
+#[derive(Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct PayToken {
+    pub mint: Pubkey,
+    // price in REWARD_TOKEN_DECIMAL units, converted with the mint's decimals on payment
+    pub price: u64,
+    pub decimals: u8,
+    // fee on payments in this mint, in bps
+    pub fee_bps: u64,
+    pub is_enabled: bool,
+}
+
+#[account]
+#[derive(Default)]
+pub struct PayTokenList {
+    pub token_list: [PayToken; MAX_PAY_TOKEN_COUNT],
+    pub count: u8,
+}
+
+impl PayTokenList {
+    pub fn get_pay_token(&self, mint: &Pubkey) -> Result<PayToken> {
+        let pay_token = self.token_list[..self.count as usize]
+            .iter()
+            .find(|pay_token| pay_token.mint.eq(mint))
+            .ok_or(SpinError::IncorrectPayMint)?;
+        require!(pay_token.is_enabled, SpinError::PayTokenDisabled);
+
+        Ok(*pay_token)
+    }
+
+    // also finds disabled pay tokens, for refunds
+    pub fn find_pay_token(&self, mint: &Pubkey) -> Option<PayToken> {
+        self.token_list[..self.count as usize].iter().find(|pay_token| pay_token.mint.eq(mint)).copied()
+    }
+
+    pub fn get_or_add_pay_token(&mut self, mint: Pubkey, fee_bps: u64) -> Result<&mut PayToken> {
+        let count = self.count as usize;
+        let index = match self.token_list[..count].iter().position(|pay_token| pay_token.mint.eq(&mint)) {
+            Some(index) => index,
+            None => {
+                require!(count < MAX_PAY_TOKEN_COUNT, SpinError::CountOverflowAddItem);
+                self.token_list[count] = PayToken { mint, fee_bps, ..Default::default() };
+                self.count += 1;
+                count
+            }
+        };
+
+        Ok(&mut self.token_list[index])
+    }
+}
 
 #[account]
 #[derive(Default)]
@@ -28,6 +240,143 @@ pub struct UserState {
     pub user: Pubkey,
 
     pub round_num: u32,
+
+    // prepaid spin credits per (pool, pay mint) in mint base units, SOL under SOL_PAY_MINT
+    pub credit_pool_list: [Pubkey; MAX_CREDIT_COUNT],
+    pub credit_mint_list: [Pubkey; MAX_CREDIT_COUNT],
+    pub credit_amount_list: [u64; MAX_CREDIT_COUNT],
+    pub credit_count: u8,
+
+    // one free spin per period and pool; entries whose cooldown ran out are reused for other pools
+    pub free_spin_pool_list: [Pubkey; MAX_FREE_SPIN_WHEEL_COUNT],
+    pub last_free_spin_ts_list: [i64; MAX_FREE_SPIN_WHEEL_COUNT],
+    pub free_spin_ready_at_list: [i64; MAX_FREE_SPIN_WHEEL_COUNT],
+    pub free_spin_count: u8,
+}
+
+impl UserState {
+    pub fn take_free_spin(&mut self, pool: &Pubkey, now: i64, free_spin_period: i64) -> Result<()> {
+        require!(free_spin_period > 0, SpinError::FreeSpinDisabled);
+        let ready_at = now.checked_add(free_spin_period).ok_or(SpinError::AmountOverflow)?;
+
+        let count = self.free_spin_count as usize;
+        let index = match self.free_spin_pool_list[..count].iter().position(|item| item.eq(pool)) {
+            Some(index) => {
+                // the pool's current period applies, also to spins taken before it changed
+                let last = self.last_free_spin_ts_list[index];
+                require!(now >= last.saturating_add(free_spin_period), SpinError::FreeSpinCooldown);
+                index
+            }
+            None if count < MAX_FREE_SPIN_WHEEL_COUNT => {
+                self.free_spin_count += 1;
+                count
+            }
+            None => self.free_spin_ready_at_list[..count]
+                .iter()
+                .position(|item| *item <= now)
+                .ok_or(SpinError::FreeSpinCooldownListFull)?,
+        };
+        self.free_spin_pool_list[index] = *pool;
+        self.last_free_spin_ts_list[index] = now;
+        self.free_spin_ready_at_list[index] = ready_at;
+
+        Ok(())
+    }
+
+    fn credit_index(&self, pool: &Pubkey, mint: &Pubkey) -> Option<usize> {
+        (0..self.credit_count as usize)
+            .find(|i| self.credit_pool_list[*i].eq(pool) && self.credit_mint_list[*i].eq(mint))
+    }
+
+    pub fn credit_of(&self, pool: &Pubkey, mint: &Pubkey) -> u64 {
+        self.credit_index(pool, mint).map_or(0, |index| self.credit_amount_list[index])
+    }
+
+    pub fn add_credit(&mut self, pool: &Pubkey, mint: &Pubkey, amount: u64) -> Result<()> {
+        let index = match self.credit_index(pool, mint) {
+            Some(index) => index,
+            None => {
+                require!((self.credit_count as usize) < MAX_CREDIT_COUNT, SpinError::CountOverflowAddItem);
+                let index = self.credit_count as usize;
+                self.credit_pool_list[index] = *pool;
+                self.credit_mint_list[index] = *mint;
+                self.credit_amount_list[index] = 0;
+                self.credit_count += 1;
+                index
+            }
+        };
+        self.credit_amount_list[index] = self.credit_amount_list[index].checked_add(amount).ok_or(SpinError::AmountOverflow)?;
+
+        Ok(())
+    }
+
+    // emptied balances give their slot back
+    pub fn spend_credit(&mut self, pool: &Pubkey, mint: &Pubkey, amount: u64) -> Result<()> {
+        let index = self.credit_index(pool, mint).ok_or(SpinError::InsufficientCredits)?;
+        self.credit_amount_list[index] = self.credit_amount_list[index]
+            .checked_sub(amount)
+            .ok_or(SpinError::InsufficientCredits)?;
+
+        if self.credit_amount_list[index] == 0 {
+            let last = self.credit_count as usize - 1;
+            self.credit_pool_list[index] = self.credit_pool_list[last];
+            self.credit_mint_list[index] = self.credit_mint_list[last];
+            self.credit_amount_list[index] = self.credit_amount_list[last];
+            self.credit_pool_list[last] = Pubkey::default();
+            self.credit_mint_list[last] = Pubkey::default();
+            self.credit_amount_list[last] = 0;
+            self.credit_count -= 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct SpinRequest {
+    // user
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub round_num: u32,
+
+    // hash of the seed revealed in settle_spin
+    pub commitment: [u8; 32],
+    pub target_slot: u64,
+
+    pub pay_mint: Pubkey,
+    pub pay_amount: u64,
+
+    // settled through a RandomnessRequest instead of the commitment
+    pub use_oracle: bool,
+
+    // number of results drawn at settlement, pay_amount covers all of them
+    pub spin_count: u8,
+
+    // paid by burning spin tickets instead of pay_amount
+    pub is_ticket: bool,
+
+    // free spin, drawn from the free spin item list
+    pub is_free: bool,
+
+    // what the payment left in the vault after the revenue split and jackpot share,
+    // in the pay mint's base units; refunded if an oracle spin expires
+    pub vault_amount: u64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct RandomnessRequest {
+    pub spin_request: Pubkey,
+    pub pool: Pubkey,
+    pub requester: Pubkey,
+
+    // message the oracle signs together with this account's key
+    pub seed: [u8; 32],
+
+    pub oracle: Pubkey,
+    pub randomness: [u8; 32],
+    pub is_fulfilled: bool,
 }
 
 #[account]
@@ -35,20 +384,28 @@ pub struct UserState {
 pub struct UserPendingClaimState {
     // user
     pub user: Pubkey,
+    pub pool: Pubkey,
     pub is_claimed: u8,
     pub round_num: u32,
 
-    pub pending_mint_list: [Pubkey; REWARD_TOKEN_COUNT_PER_ITEM],
-    pub pending_amount_list: [u64; REWARD_TOKEN_COUNT_PER_ITEM],
-    pub is_claimed_list: [bool; REWARD_TOKEN_COUNT_PER_ITEM],
+    pub pending_mint_list: [Pubkey; MAX_PENDING_REWARD_COUNT],
+    pub pending_amount_list: [u64; MAX_PENDING_REWARD_COUNT],
+    pub is_claimed_list: [bool; MAX_PENDING_REWARD_COUNT],
     pub is_sol: bool,
     pub sol_amount: u64,
     pub count: u8,
 }
 
 impl UserPendingClaimState {
+    // Results of a bulk spin paying the same mint add up in one entry.
     pub fn add_item(&mut self, pending_mint: Pubkey, amount: u64) -> Result<()> {
-        require!(self.count <= REWARD_TOKEN_COUNT_PER_ITEM as u8, SpinError::CountOverflowAddItem);
+        for i in 0..self.count as usize {
+            if self.pending_mint_list[i].eq(&pending_mint) && !self.is_claimed_list[i] {
+                self.pending_amount_list[i] = self.pending_amount_list[i].checked_add(amount).ok_or(SpinError::AmountOverflow)?;
+                return Ok(());
+            }
+        }
+        require!((self.count as usize) < MAX_PENDING_REWARD_COUNT, SpinError::CountOverflowAddItem);
 
         self.pending_mint_list[self.count as usize] = pending_mint;
         self.pending_amount_list[self.count as usize] = amount;
@@ -56,6 +413,33 @@ impl UserPendingClaimState {
 
         Ok(())
     }
+
+    // Marks the SOL reward claimed and returns its amount.
+    pub fn take_sol_reward(&mut self) -> Result<u64> {
+        require!(self.is_sol, SpinError::InvalidReward);
+        require!(self.is_claimed == 0, SpinError::RewardAlreadyClaimed);
+
+        self.is_claimed = 1;
+        Ok(self.sol_amount)
+    }
+
+    // Marks the first unclaimed entry for `mint` claimed and returns its amount.
+    pub fn take_token_reward(&mut self, mint: &Pubkey) -> Result<u64> {
+        let mut is_found = false;
+        for i in 0..self.count as usize {
+            if !self.pending_mint_list[i].eq(mint) {
+                continue;
+            }
+            is_found = true;
+            if !self.is_claimed_list[i] {
+                self.is_claimed_list[i] = true;
+                return Ok(self.pending_amount_list[i]);
+            }
+        }
+
+        require!(!is_found, SpinError::RewardAlreadyClaimed);
+        err!(SpinError::InvalidReward)
+    }
 }
 
 #[account]
@@ -63,55 +447,594 @@ impl UserPendingClaimState {
 pub struct AdminInfo {
     pub admin_list: [Pubkey; ADMIN_MAX_COUNT],
     pub count: u8,
+    // ROLE_* bitmask per admin, index-aligned with admin_list
+    pub role_list: [u8; ADMIN_MAX_COUNT],
+}
+
+impl AdminInfo {
+    fn index_of(&self, admin: &Pubkey) -> Option<usize> {
+        self.admin_list[..self.count as usize].iter().position(|item| item.eq(admin))
+    }
+
+    pub fn is_admin(&self, admin: &Pubkey) -> bool {
+        self.index_of(admin).is_some()
+    }
+
+    pub fn roles_of(&self, admin: &Pubkey) -> u8 {
+        self.index_of(admin).map_or(0, |index| self.role_list[index])
+    }
+
+    pub fn has_role(&self, admin: &Pubkey, role: u8) -> bool {
+        self.roles_of(admin) & role == role
+    }
+
+    pub fn add_admin(&mut self, admin: Pubkey, roles: u8) -> Result<()> {
+        require!(roles & !ROLE_ALL == 0, SpinError::InvalidRole);
+        require!(!self.is_admin(&admin), SpinError::AdminAlreadyExists);
+        require!((self.count as usize) < ADMIN_MAX_COUNT, SpinError::CountOverflowAddItem);
+
+        self.admin_list[self.count as usize] = admin;
+        self.role_list[self.count as usize] = roles;
+        self.count += 1;
+
+        Ok(())
+    }
+
+    pub fn delete_admin(&mut self, admin: Pubkey) -> Result<()> {
+        let index = self.index_of(&admin).ok_or(SpinError::AdminNotFound)?;
+        let last = self.count as usize - 1;
+
+        self.admin_list[index] = self.admin_list[last];
+        self.role_list[index] = self.role_list[last];
+        self.admin_list[last] = Pubkey::default();
+        self.role_list[last] = 0;
+        self.count -= 1;
+
+        Ok(())
+    }
+
+    pub fn grant_role(&mut self, admin: Pubkey, roles: u8) -> Result<()> {
+        require!(roles != 0 && roles & !ROLE_ALL == 0, SpinError::InvalidRole);
+        let index = self.index_of(&admin).ok_or(SpinError::AdminNotFound)?;
+        self.role_list[index] |= roles;
+
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, admin: Pubkey, roles: u8) -> Result<()> {
+        require!(roles != 0 && roles & !ROLE_ALL == 0, SpinError::InvalidRole);
+        let index = self.index_of(&admin).ok_or(SpinError::AdminNotFound)?;
+        self.role_list[index] &= !roles;
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Treasury {
+    // withdrawals may only go to these wallets
+    pub dest_list: [Pubkey; MAX_TREASURY_DEST_COUNT],
+    pub dest_count: u8,
+
+    // per-epoch withdrawal caps, SOL is keyed by SOL_PAY_MINT; assets without a cap can't be withdrawn
+    pub cap_mint_list: [Pubkey; MAX_TREASURY_CAP_COUNT],
+    pub cap_amount_list: [u64; MAX_TREASURY_CAP_COUNT],
+    pub withdrawn_amount_list: [u64; MAX_TREASURY_CAP_COUNT],
+    pub withdrawn_epoch_list: [u64; MAX_TREASURY_CAP_COUNT],
+    pub cap_count: u8,
+}
+
+impl Treasury {
+    pub fn is_destination(&self, destination: &Pubkey) -> bool {
+        self.dest_list[..self.dest_count as usize].contains(destination)
+    }
+
+    pub fn set_destination(&mut self, destination: Pubkey, is_allowed: bool) -> Result<()> {
+        let count = self.dest_count as usize;
+        let index = self.dest_list[..count].iter().position(|item| item.eq(&destination));
+
+        match (index, is_allowed) {
+            (None, true) => {
+                require!(count < MAX_TREASURY_DEST_COUNT, SpinError::CountOverflowAddItem);
+                self.dest_list[count] = destination;
+                self.dest_count += 1;
+            }
+            (Some(index), false) => {
+                self.dest_list[index] = self.dest_list[count - 1];
+                self.dest_list[count - 1] = Pubkey::default();
+                self.dest_count -= 1;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn set_cap(&mut self, mint: Pubkey, epoch_cap: u64) -> Result<()> {
+        let count = self.cap_count as usize;
+        let index = match self.cap_mint_list[..count].iter().position(|item| item.eq(&mint)) {
+            Some(index) => index,
+            None => {
+                require!(count < MAX_TREASURY_CAP_COUNT, SpinError::CountOverflowAddItem);
+                self.cap_mint_list[count] = mint;
+                self.cap_count += 1;
+                count
+            }
+        };
+        self.cap_amount_list[index] = epoch_cap;
+
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, destination: &Pubkey, mint: &Pubkey, amount: u64, epoch: u64) -> Result<()> {
+        require!(self.is_destination(destination), SpinError::IncorrectWithdrawDestination);
+
+        let index = self.cap_mint_list[..self.cap_count as usize]
+            .iter()
+            .position(|item| item.eq(mint))
+            .ok_or(SpinError::WithdrawalCapExceeded)?;
+
+        if self.withdrawn_epoch_list[index] != epoch {
+            self.withdrawn_epoch_list[index] = epoch;
+            self.withdrawn_amount_list[index] = 0;
+        }
+
+        let withdrawn = self.withdrawn_amount_list[index].checked_add(amount).ok_or(SpinError::AmountOverflow)?;
+        require!(withdrawn <= self.cap_amount_list[index], SpinError::WithdrawalCapExceeded);
+        self.withdrawn_amount_list[index] = withdrawn;
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Multisig {
+    pub signer_list: [Pubkey; MAX_MULTISIG_SIGNER_COUNT],
+    pub signer_count: u8,
+    pub threshold: u8,
+    pub is_enabled: bool,
+    // bumped on every signer change so older proposals can't be executed
+    pub version: u64,
+    pub proposal_count: u64,
+}
+
+impl Multisig {
+    pub fn signer_index(&self, signer: &Pubkey) -> Option<usize> {
+        self.signer_list[..self.signer_count as usize].iter().position(|item| item.eq(signer))
+    }
+
+    pub fn set_signers(&mut self, signer_list: &[Pubkey], threshold: u8, is_enabled: bool) -> Result<()> {
+        require!(signer_list.len() <= MAX_MULTISIG_SIGNER_COUNT, SpinError::InvalidMultisig);
+        if is_enabled {
+            require!(threshold > 0 && threshold as usize <= signer_list.len(), SpinError::InvalidMultisig);
+        }
+        for (i, signer) in signer_list.iter().enumerate() {
+            require!(*signer != Pubkey::default(), SpinError::InvalidMultisig);
+            require!(!signer_list[..i].contains(signer), SpinError::InvalidMultisig);
+        }
+
+        self.signer_list = [Pubkey::default(); MAX_MULTISIG_SIGNER_COUNT];
+        self.signer_list[..signer_list.len()].copy_from_slice(signer_list);
+        self.signer_count = signer_list.len() as u8;
+        self.threshold = threshold;
+        self.is_enabled = is_enabled;
+        self.version += 1;
+
+        Ok(())
+    }
+
+    pub fn require_approved(&self, proposal: &Proposal) -> Result<()> {
+        require!(self.is_enabled, SpinError::MultisigDisabled);
+        require!(proposal.version == self.version, SpinError::StaleProposal);
+        require!(proposal.approval_count >= self.threshold, SpinError::ProposalThresholdNotMet);
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum MultisigAction {
+    SetItem {
+        index: u8,
+        item_mint_list: [Pubkey; REWARD_TOKEN_COUNT_PER_ITEM],
+        count: u8,
+        mint_amount_list: [u64; REWARD_TOKEN_COUNT_PER_ITEM],
+        token_type: u8,
+        ratio: u32,
+        amount: u64,
+        item_count: u8,
+    },
+    SetPayInfo { sol_price: u64 },
+    SetRevenueSplit {
+        sol_fee_bps: u64,
+        recipient_list: [Pubkey; MAX_SPLIT_RECIPIENT_COUNT],
+        share_list: [u64; MAX_SPLIT_RECIPIENT_COUNT],
+        split_count: u8,
+    },
+    SetPayTokenFee { mint: Pubkey, fee_bps: u64 },
+    AddAdmin { admin: Pubkey, roles: u8 },
+    DeleteAdmin { admin: Pubkey },
+    GrantRole { admin: Pubkey, roles: u8 },
+    RevokeRole { admin: Pubkey, roles: u8 },
+    SetMultisig {
+        signer_list: [Pubkey; MAX_MULTISIG_SIGNER_COUNT],
+        signer_count: u8,
+        threshold: u8,
+        is_enabled: bool,
+    },
+    SetConfigDelay { config_delay: i64 },
+    ProposeSuperadmin { new_superadmin: Pubkey },
+    RenounceSuperadmin,
+    SetJackpot { jackpot_bps: u64 },
+    SetWheelLimits { ratio_total: u64, max_rtp_bps: u64 },
+    SetTreasuryDestination { destination: Pubkey, is_allowed: bool },
+    SetTreasuryCap { mint: Pubkey, epoch_cap: u64 },
+    SetBulkDiscounts {
+        min_count_list: [u8; MAX_DISCOUNT_TIER_COUNT],
+        discount_bps_list: [u64; MAX_DISCOUNT_TIER_COUNT],
+        tier_count: u8,
+    },
+    SetFreeSpinPeriod { free_spin_period: i64 },
+    WithdrawSol { destination: Pubkey, amount: u64 },
+    WithdrawToken { source: Pubkey, destination: Pubkey, amount: u64 },
+    AddOracle { oracle: Pubkey },
+    DeleteOracle { oracle: Pubkey },
+    RestockItem { index: u8, stock: u32 },
+}
+
+#[account]
+pub struct Proposal {
+    pub pool: Pubkey,
+    pub proposer: Pubkey,
+    pub proposal_id: u64,
+    pub version: u64,
+    pub action: MultisigAction,
+    // bit i is set once signer_list[i] approved
+    pub approval_mask: u16,
+    pub approval_count: u8,
+}
+
+impl Proposal {
+    pub fn approve(&mut self, multisig: &Multisig, signer: &Pubkey) -> Result<()> {
+        require!(self.version == multisig.version, SpinError::StaleProposal);
+        let index = multisig.signer_index(signer).ok_or(SpinError::IncorrectMultisigSigner)?;
+        require!(self.approval_mask & (1 << index) == 0, SpinError::ProposalAlreadyApproved);
+
+        self.approval_mask |= 1 << index;
+        self.approval_count += 1;
+
+        Ok(())
+    }
+}
+
+
+// space : 32 * 10 + 8 * 10 + 1
+#[zero_copy]
+#[repr(C, packed)]
+#[derive(Default)]
+pub struct ItemRewardMints {
+    pub item_mint_list: [Pubkey; REWARD_TOKEN_COUNT_PER_ITEM],
+    // paid per mint, in that mint's base units
+    pub amount_list: [u64; REWARD_TOKEN_COUNT_PER_ITEM],
+    pub count: u8,
+}
+
+// space : 6317
+#[account(zero_copy)]
+#[repr(C, packed)]
+pub struct SpinItemList {
+    pub reward_mint_list: [ItemRewardMints; SPIN_ITEM_COUNT],   // 401 * 15
+    pub token_type_list: [u8; SPIN_ITEM_COUNT],   // 15
+    pub ratio_list: [u32; SPIN_ITEM_COUNT],  // 4 * 15
+    pub amount_list: [u64; SPIN_ITEM_COUNT],    // 8 * 15
+    // 1 when an admin took the item out of the draw, its weight goes to the rest
+    pub item_paused_list: [u8; SPIN_ITEM_COUNT],    // 15
+    // limited items: remaining stock, and the item that takes their weight once sold out
+    pub has_stock_limit_list: [u8; SPIN_ITEM_COUNT],    // 15
+    pub stock_list: [u32; SPIN_ITEM_COUNT],    // 4 * 15
+    pub fallback_index_list: [u8; SPIN_ITEM_COUNT],    // 15
+    pub last_spinindex: u8, // 1
+    pub count: u8, // 1
+}
+
+// Staged copy of the wheel's items and prices, readable by frontends until activated.
+#[account(zero_copy)]
+#[repr(C, packed)]
+pub struct PendingWheelConfig {
+    pub items: SpinItemList,
+    pub sol_price: u64,
+    pub price_mint_list: [Pubkey; MAX_PAY_TOKEN_COUNT],
+    pub price_list: [u64; MAX_PAY_TOKEN_COUNT],
+    pub price_count: u8,
+    pub activates_at: i64,
+    pub is_staged: u8,
+    pub ratio_total: u64,
+    pub max_rtp_bps: u64,
+    pub jackpot_bps: u64,
+    // stock set on activation for items whose is_restock flag is set
+    pub restock_list: [u32; SPIN_ITEM_COUNT],
+    pub is_restock_list: [u8; SPIN_ITEM_COUNT],
+    // index-aligned with price_mint_list
+    pub is_enabled_list: [u8; MAX_PAY_TOKEN_COUNT],
+    pub discount_min_count_list: [u8; MAX_DISCOUNT_TIER_COUNT],
+    pub discount_bps_list: [u64; MAX_DISCOUNT_TIER_COUNT],
+    pub discount_tier_count: u8,
+    pub config_delay: i64,
+}
+
+impl PendingWheelConfig {
+    // the first change of a round starts from the active config
+    pub fn stage_from(&mut self, state: &SpinItemList, pool: &Pool, pay_tokens: &PayTokenList) {
+        self.items = *state;
+        self.sol_price = pool.sol_price;
+        self.price_mint_list = [Pubkey::default(); MAX_PAY_TOKEN_COUNT];
+        self.price_list = [0; MAX_PAY_TOKEN_COUNT];
+        self.is_enabled_list = [0; MAX_PAY_TOKEN_COUNT];
+        for i in 0..pay_tokens.count as usize {
+            self.price_mint_list[i] = pay_tokens.token_list[i].mint;
+            self.price_list[i] = pay_tokens.token_list[i].price;
+            self.is_enabled_list[i] = pay_tokens.token_list[i].is_enabled as u8;
+        }
+        self.price_count = pay_tokens.count;
+        self.ratio_total = pool.ratio_total;
+        self.max_rtp_bps = pool.max_rtp_bps;
+        self.jackpot_bps = pool.jackpot_bps;
+        self.discount_min_count_list = pool.discount_min_count_list;
+        self.discount_bps_list = pool.discount_bps_list;
+        self.discount_tier_count = pool.discount_tier_count;
+        self.config_delay = pool.config_delay;
+        self.restock_list = [0; SPIN_ITEM_COUNT];
+        self.is_restock_list = [0; SPIN_ITEM_COUNT];
+        self.is_staged = 1;
+    }
+
+    pub fn set_token_price(&mut self, mint: &Pubkey, price: u64) -> Result<()> {
+        let mint_list = self.price_mint_list;
+        let index = mint_list[..self.price_count as usize]
+            .iter()
+            .position(|item| item.eq(mint))
+            .ok_or(SpinError::IncorrectPayMint)?;
+        self.price_list[index] = price;
+
+        Ok(())
+    }
+
+    // Also takes pay tokens added to the live list after this config was staged.
+    pub fn set_pay_token(&mut self, mint: &Pubkey, price: u64, is_enabled: bool) -> Result<()> {
+        let count = self.price_count as usize;
+        let mint_list = self.price_mint_list;
+        let index = match mint_list[..count].iter().position(|item| item.eq(mint)) {
+            Some(index) => index,
+            None => {
+                require!(count < MAX_PAY_TOKEN_COUNT, SpinError::CountOverflowAddItem);
+                self.price_mint_list[count] = *mint;
+                self.price_count += 1;
+                count
+            }
+        };
+        self.price_list[index] = price;
+        self.is_enabled_list[index] = is_enabled as u8;
+
+        Ok(())
+    }
+
+    pub fn set_bulk_discounts(&mut self, min_count_list: &[u8], discount_bps_list: &[u64]) -> Result<()> {
+        (self.discount_min_count_list, self.discount_bps_list, self.discount_tier_count) =
+            discount_tiers(min_count_list, discount_bps_list)?;
+
+        Ok(())
+    }
+
+    fn staged_pricing(&self, pool: &Pool, pay_tokens: &PayTokenList) -> (Pool, PayTokenList) {
+        let mut staged_pool = pool.clone();
+        staged_pool.sol_price = self.sol_price;
+        staged_pool.ratio_total = self.ratio_total;
+        staged_pool.max_rtp_bps = self.max_rtp_bps;
+        staged_pool.jackpot_bps = self.jackpot_bps;
+        staged_pool.discount_min_count_list = self.discount_min_count_list;
+        staged_pool.discount_bps_list = self.discount_bps_list;
+        staged_pool.discount_tier_count = self.discount_tier_count;
+        staged_pool.config_delay = self.config_delay;
+
+        let mut staged_tokens = pay_tokens.clone();
+        let mint_list = self.price_mint_list;
+        let price_list = self.price_list;
+        let is_enabled_list = self.is_enabled_list;
+        for pay_token in staged_tokens.token_list[..staged_tokens.count as usize].iter_mut() {
+            if let Some(index) = mint_list[..self.price_count as usize].iter().position(|item| item.eq(&pay_token.mint)) {
+                pay_token.price = price_list[index];
+                pay_token.is_enabled = is_enabled_list[index] != 0;
+            }
+        }
+
+        (staged_pool, staged_tokens)
+    }
+
+    pub fn validate(&self, pool: &Pool, pay_tokens: &PayTokenList) -> Result<()> {
+        let (staged_pool, staged_tokens) = self.staged_pricing(pool, pay_tokens);
+        let items = self.items;
+        items.validate(&staged_pool, &staged_tokens)
+    }
+
+    pub fn apply(&mut self, state: &mut SpinItemList, pool: &mut Pool, pay_tokens: &mut PayTokenList) -> Result<()> {
+        require!(self.is_staged == 1, SpinError::ConfigNotStaged);
+        self.validate(pool, pay_tokens)?;
+
+        let (staged_pool, staged_tokens) = self.staged_pricing(pool, pay_tokens);
+        // stock and item pauses are live state, only restocking changes the stock;
+        // an emergency pause made while the change waited must survive it
+        let last_spinindex = state.last_spinindex;
+        let mut item_paused_list = state.item_paused_list;
+        let mut stock_list = state.stock_list;
+        for ((stock, is_restock), restock) in stock_list.iter_mut().zip(self.is_restock_list).zip(self.restock_list) {
+            if is_restock != 0 {
+                *stock = restock;
+            }
+        }
+        *state = self.items;
+        for is_paused in item_paused_list.iter_mut().skip(state.count as usize) {
+            *is_paused = 0;
+        }
+        state.last_spinindex = last_spinindex;
+        state.item_paused_list = item_paused_list;
+        state.stock_list = stock_list;
+        pool.sol_price = staged_pool.sol_price;
+        pool.ratio_total = staged_pool.ratio_total;
+        pool.max_rtp_bps = staged_pool.max_rtp_bps;
+        pool.jackpot_bps = staged_pool.jackpot_bps;
+        pool.discount_min_count_list = staged_pool.discount_min_count_list;
+        pool.discount_bps_list = staged_pool.discount_bps_list;
+        pool.discount_tier_count = staged_pool.discount_tier_count;
+        pool.config_delay = staged_pool.config_delay;
+        *pay_tokens = staged_tokens;
+
+        self.clear();
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.is_staged = 0;
+        self.activates_at = 0;
+    }
+}
+
+// Accrued jackpot per currency; SOL sits in the jackpot vault PDA and tokens in
+// its ATAs.
+#[account]
+#[derive(Default)]
+pub struct Jackpot {
+    pub sol_balance: u64,
+    pub mint_list: [Pubkey; MAX_PAY_TOKEN_COUNT],
+    pub balance_list: [u64; MAX_PAY_TOKEN_COUNT],
+    pub count: u8,
+}
+
+// What a jackpot hit pays, index-aligned with the item's reward mints.
+#[derive(Default)]
+pub struct JackpotPayout {
+    pub sol_amount: u64,
+    pub mint_list: [Pubkey; REWARD_TOKEN_COUNT_PER_ITEM],
+    pub amount_list: [u64; REWARD_TOKEN_COUNT_PER_ITEM],
+    pub count: u8,
+}
+
+impl Jackpot {
+    pub fn add_sol(&mut self, amount: u64) -> Result<()> {
+        self.sol_balance = self.sol_balance.checked_add(amount).ok_or(SpinError::AmountOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn add(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        let count = self.count as usize;
+        let index = match self.mint_list[..count].iter().position(|item| item.eq(mint)) {
+            Some(index) => index,
+            None => {
+                require!(count < MAX_PAY_TOKEN_COUNT, SpinError::CountOverflowAddItem);
+                self.mint_list[count] = *mint;
+                self.count += 1;
+                count
+            }
+        };
+        self.balance_list[index] = self.balance_list[index].checked_add(amount).ok_or(SpinError::AmountOverflow)?;
+
+        Ok(())
+    }
+
+    // Takes payout_bps of the SOL jackpot and of each of the item's mints.
+    pub fn take_payout(&mut self, payout_bps: u64, reward_mints: &ItemRewardMints, sol_available: u64) -> Result<JackpotPayout> {
+        let mut payout = JackpotPayout {
+            sol_amount: dev_fee(self.sol_balance.min(sol_available), payout_bps)?,
+            mint_list: reward_mints.item_mint_list,
+            count: reward_mints.count,
+            ..Default::default()
+        };
+        self.sol_balance -= payout.sol_amount;
+
+        for i in 0..reward_mints.count as usize {
+            let mint = payout.mint_list[i];
+            if let Some(index) = self.mint_list[..self.count as usize].iter().position(|item| item.eq(&mint)) {
+                let amount = dev_fee(self.balance_list[index], payout_bps)?;
+                self.balance_list[index] -= amount;
+                payout.amount_list[i] = amount;
+            }
+        }
+
+        Ok(payout)
+    }
+}
+
+// Rewards written to pending claim accounts but not paid out yet.
+#[account(zero_copy)]
+#[repr(C, packed)]
+pub struct Liabilities {
+    pub sol_amount: u64,
+    pub mint_list: [Pubkey; MAX_REWARD_TOKEN_COUNT],
+    pub amount_list: [u64; MAX_REWARD_TOKEN_COUNT],
+    pub count: u8,
 }
 
-impl AdminInfo {
-    pub fn add_admin(&mut self, admin: Pubkey) -> Result<()> {
-        require!(self.count <= ADMIN_MAX_COUNT as u8, SpinError::CountOverflowAddItem);
+impl Liabilities {
+    fn index_of(&self, mint: &Pubkey) -> Option<usize> {
+        let mint_list = self.mint_list;
+        mint_list[..self.count as usize].iter().position(|item| item.eq(mint))
+    }
 
-        self.admin_list[self.count as usize] = admin;
-        self.count += 1;
+    pub fn get(&self, mint: &Pubkey) -> u64 {
+        self.index_of(mint).map_or(0, |index| self.amount_list[index])
+    }
+
+    pub fn add(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        let index = match self.index_of(mint) {
+            Some(index) => index,
+            None => {
+                require!((self.count as usize) < MAX_REWARD_TOKEN_COUNT, SpinError::CountOverflowAddItem);
+                self.mint_list[self.count as usize] = *mint;
+                self.amount_list[self.count as usize] = 0;
+                self.count += 1;
+                self.count as usize - 1
+            }
+        };
+        self.amount_list[index] = self.amount_list[index].checked_add(amount).ok_or(SpinError::AmountOverflow)?;
 
         Ok(())
     }
 
-    pub fn delete_admin(&mut self, admin: Pubkey) -> Result<()> {
-        for i in 0..self.count {
-            if self.admin_list[i as usize].eq(&admin) {
-                self.admin_list[i as usize] = self.admin_list[self.count as usize - 1];
-                self.count -= 1;
-                break;
-            }
+    pub fn sub(&mut self, mint: &Pubkey, amount: u64) {
+        if let Some(index) = self.index_of(mint) {
+            self.amount_list[index] = self.amount_list[index].saturating_sub(amount);
         }
+    }
+
+    pub fn add_sol(&mut self, amount: u64) -> Result<()> {
+        self.sol_amount = self.sol_amount.checked_add(amount).ok_or(SpinError::AmountOverflow)?;
 
         Ok(())
     }
-}
 
+    pub fn sub_sol(&mut self, amount: u64) {
+        self.sol_amount = self.sol_amount.saturating_sub(amount);
+    }
 
-// space : 32 * 10 + 1
-#[zero_copy]
-#[derive(Default, AnchorSerialize, AnchorDeserialize)]
-pub struct ItemRewardMints {
-    pub item_mint_list: [Pubkey; REWARD_TOKEN_COUNT_PER_ITEM],
-    pub count: u8,
-}
-
-// space : 5020 // old : 4975
-#[account(zero_copy)]
-#[repr(packed)]
-pub struct SpinItemList {
-    pub reward_mint_list: [ItemRewardMints; SPIN_ITEM_COUNT],   // 321 * 15
-    pub token_type_list: [u8; SPIN_ITEM_COUNT],   // 15
-    pub ratio_list: [u32; SPIN_ITEM_COUNT],  // 4 * 15
-    pub amount_list: [u64; SPIN_ITEM_COUNT],    // 8 * 15
-    pub last_spinindex: u8, // 1
-    pub count: u8, // 1
+    // drops whatever the pending account still owes, for forfeited claims
+    pub fn release(&mut self, pending: &UserPendingClaimState) {
+        if pending.is_sol && pending.is_claimed == 0 {
+            self.sub_sol(pending.sol_amount);
+        }
+        for i in 0..pending.count as usize {
+            if !pending.is_claimed_list[i] {
+                self.sub(&pending.pending_mint_list[i], pending.pending_amount_list[i]);
+            }
+        }
+    }
 }
 
 impl ItemRewardMints {
-    pub fn add_reward_item(&mut self, reward_mint: Pubkey) {
+    pub fn add_reward_item(&mut self, reward_mint: Pubkey, amount: u64) {
         self.item_mint_list[self.count as usize] = reward_mint;
+        self.amount_list[self.count as usize] = amount;
         self.count += 1;
     }
 }
@@ -128,6 +1051,10 @@ impl Default for SpinItemList {
             token_type_list: [0; SPIN_ITEM_COUNT],
             ratio_list: [0; SPIN_ITEM_COUNT],
             amount_list: [0; SPIN_ITEM_COUNT],
+            item_paused_list: [0; SPIN_ITEM_COUNT],
+            has_stock_limit_list: [0; SPIN_ITEM_COUNT],
+            stock_list: [0; SPIN_ITEM_COUNT],
+            fallback_index_list: [NO_FALLBACK_ITEM; SPIN_ITEM_COUNT],
             last_spinindex: 0,
             count: 0,
         }
@@ -136,7 +1063,7 @@ impl Default for SpinItemList {
 
 impl SpinItemList {
     pub fn add_spinitem(&mut self, item_mint_list: ItemRewardMints, token_type: u8, ratio: u32, amount: u64,) -> Result<()> {
-        require!(self.count <= SPIN_ITEM_COUNT as u8, SpinError::CountOverflowAddItem);
+        require!((self.count as usize) < SPIN_ITEM_COUNT, SpinError::CountOverflowAddItem);
 
         self.reward_mint_list[self.count as usize] = item_mint_list;
         self.token_type_list[self.count as usize] = token_type;
@@ -154,6 +1081,7 @@ impl SpinItemList {
         self.token_type_list[index as usize] = token_type;
         self.ratio_list[index as usize] = ratio;
         self.amount_list[index as usize] = amount;
+        self.item_paused_list[index as usize] = 0;
         if self.count <= index {
             self.count = index + 1;
         }
@@ -165,24 +1093,212 @@ impl SpinItemList {
         self.count = 0;
     }
 
-    pub fn get_spinresult(&mut self, rand: u32) {
-        let ctime = Clock::get().unwrap();
-        let c = ctime.unix_timestamp * rand as i64;
-        let mut r = (c % 101) as u32;
-        if r == 100 {
-            r = 99;
+    // Shrinking the list clears the dropped slots so no stale weight is left behind.
+    pub fn set_count(&mut self, count: u8) -> Result<()> {
+        require!(count as usize <= SPIN_ITEM_COUNT, SpinError::CountOverflowAddItem);
+
+        for i in count as usize..SPIN_ITEM_COUNT {
+            self.reward_mint_list[i] = ItemRewardMints::default();
+            self.token_type_list[i] = 0;
+            self.ratio_list[i] = 0;
+            self.amount_list[i] = 0;
+            self.item_paused_list[i] = 0;
+            self.has_stock_limit_list[i] = 0;
+            self.stock_list[i] = 0;
+            self.fallback_index_list[i] = NO_FALLBACK_ITEM;
+        }
+        self.count = count;
+
+        Ok(())
+    }
+
+    pub fn total_ratio(&self) -> u64 {
+        let ratio_list = self.ratio_list;
+        ratio_list[..(self.count as usize).min(SPIN_ITEM_COUNT)].iter().map(|ratio| *ratio as u64).sum()
+    }
+
+    // Sum of ratio * value over what items pay out, each currency valued in bps of
+    // the price of one spin in it: pool.sol_price for the item amount of SOL items,
+    // the pay token price for reward mints. Disabled pay tokens still price their
+    // mint, rewards in a currency without a price are refused. Divided by
+    // pool.ratio_total this is the expected payout of one undiscounted spin.
+    fn weighted_payout(&self, pool: &Pool, pay_tokens: &PayTokenList) -> Result<u128> {
+        let ratio_list = self.ratio_list;
+        let amount_list = self.amount_list;
+        let value_bps = |amount: u64, price: u64| -> Result<u128> {
+            require!(price > 0, SpinError::UnpricedReward);
+            Ok(amount as u128 * BPS_DENOMINATOR as u128 / price as u128)
+        };
+
+        let mut payout: u128 = 0;
+        for i in 0..self.count as usize {
+            // jackpot items are funded by pool.jackpot_bps, see validate
+            if self.token_type_list[i] == TOKEN_TYPE_JACKPOT {
+                continue;
+            }
+            let mut value = 0;
+            if self.token_type_list[i] == 2 && amount_list[i] > 0 {
+                value += value_bps(amount_list[i], pool.sol_price)?;
+            }
+            let rewards = self.reward_mint_list[i];
+            let mint_list = rewards.item_mint_list;
+            let mint_amount_list = rewards.amount_list;
+            for (mint, amount) in mint_list.iter().zip(mint_amount_list).take(rewards.count as usize) {
+                if amount == 0 {
+                    continue;
+                }
+                let pay_token = pay_tokens.find_pay_token(mint).ok_or(SpinError::UnpricedReward)?;
+                value += value_bps(amount, to_mint_amount(pay_token.price, pay_token.decimals)?)?;
+            }
+            payout += ratio_list[i] as u128 * value;
+        }
+
+        Ok(payout)
+    }
+
+    // The free spin item list only gets these checks, free spins have no price to bound its RTP.
+    pub fn validate_items(&self, pool: &Pool) -> Result<()> {
+        require!(self.count as usize <= SPIN_ITEM_COUNT, SpinError::CountOverflowAddItem);
+        require!(pool.ratio_total > 0, SpinError::ZeroSpinWeight);
+
+        // a bulk spin settles into one pending claim, which holds one entry per mint
+        let mut reward_mints: Vec<Pubkey> = Vec::new();
+        let ratio_list = self.ratio_list;
+        for (i, ratio) in ratio_list.iter().enumerate() {
+            require!(
+                self.reward_mint_list[i].count as usize <= REWARD_TOKEN_COUNT_PER_ITEM,
+                SpinError::CountOverflowAddItem
+            );
+            if i >= self.count as usize {
+                require!(*ratio == 0, SpinError::StaleSpinItem);
+                continue;
+            }
+            let item_mint_list = self.reward_mint_list[i].item_mint_list;
+            for mint in item_mint_list.iter().take(self.reward_mint_list[i].count as usize) {
+                if !reward_mints.contains(mint) {
+                    reward_mints.push(*mint);
+                }
+            }
+            if self.token_type_list[i] == TOKEN_TYPE_JACKPOT {
+                require!(self.amount_list[i] <= BPS_DENOMINATOR, SpinError::InvalidJackpotPayout);
+            }
+            if self.has_stock_limit_list[i] != 0 {
+                let fallback = self.fallback_index_list[i];
+                require!(
+                    fallback == NO_FALLBACK_ITEM || (fallback < self.count && fallback as usize != i),
+                    SpinError::InvalidFallbackItem
+                );
+            }
+        }
+
+        require!(reward_mints.len() <= MAX_PENDING_REWARD_COUNT, SpinError::TooManyRewardMints);
+
+        // items are added one at a time, so a partial wheel may stay below the total;
+        // settling a spin requires it to be complete
+        require!(self.total_ratio() <= pool.ratio_total, SpinError::SpinWeightsOverflow);
+
+        Ok(())
+    }
+
+    pub fn validate(&self, pool: &Pool, pay_tokens: &PayTokenList) -> Result<()> {
+        self.validate_items(pool)?;
+
+        // bulk discounts lower the effective price, check against the deepest one
+        let max_discount_bps = pool.max_discount_bps();
+        let mut prices = vec![pool.sol_price];
+        for pay_token in pay_tokens.token_list[..pay_tokens.count as usize].iter() {
+            if pay_token.is_enabled {
+                prices.push(to_mint_amount(pay_token.price, pay_token.decimals)?);
+            }
+        }
+        let mut pay_modes = Vec::new();
+        for price in prices {
+            let discounted_price = price - dev_fee(price, max_discount_bps)?;
+            if discounted_price > 0 {
+                pay_modes.push((price, discounted_price));
+            }
+        }
+        // nothing to bound while no pay mode charges for a spin
+        if pay_modes.is_empty() {
+            return Ok(());
+        }
+
+        let weighted_payout = self.weighted_payout(pool, pay_tokens)?;
+        for (price, discounted_price) in pay_modes {
+            // everything paid into the jackpot is eventually paid back out
+            let rtp_bps = weighted_payout.checked_mul(price as u128).ok_or(SpinError::RtpTooHigh)?
+                / (pool.ratio_total as u128 * discounted_price as u128)
+                + pool.jackpot_bps as u128;
+            require!(rtp_bps <= pool.max_rtp_bps as u128, SpinError::RtpTooHigh);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_sold_out(&self, index: usize) -> bool {
+        self.has_stock_limit_list[index] != 0 && self.stock_list[index] == 0
+    }
+
+    fn is_drawable(&self, index: usize, skip_mask: u16) -> bool {
+        self.item_paused_list[index] == 0 && skip_mask & (1 << index) == 0 && !self.is_sold_out(index)
+    }
+
+    // Paused items and items in skip_mask can't be drawn and their weight is spread
+    // over the rest; sold-out items hand their weight to the fallback item when it
+    // can still be drawn.
+    fn draw_weights(&self, skip_mask: u16) -> [u64; SPIN_ITEM_COUNT] {
+        let ratio_list = self.ratio_list;
+        let count = (self.count as usize).min(SPIN_ITEM_COUNT);
+
+        let mut weights = [0u64; SPIN_ITEM_COUNT];
+        for i in 0..count {
+            if self.is_drawable(i, skip_mask) {
+                weights[i] += ratio_list[i] as u64;
+            } else if self.is_sold_out(i) {
+                let fallback = self.fallback_index_list[i] as usize;
+                if fallback < count && self.is_drawable(fallback, skip_mask) {
+                    weights[fallback] += ratio_list[i] as u64;
+                }
+            }
         }
 
-        let r_pow = r * ((10 as u32).pow(3));
+        weights
+    }
+
+    // Draws an index among the first `count` items, weighted by draw_weights.
+    // u64 draws are rehashed until they fall below a multiple of the total
+    // weight, so every unit of weight is equally likely.
+    pub fn get_spinresult(&mut self, seed: [u8; 32], skip_mask: u16) -> Result<()> {
+        let weights = self.draw_weights(skip_mask);
+        let active = &weights[..(self.count as usize).min(SPIN_ITEM_COUNT)];
+
+        let total: u64 = active.iter().sum();
+        require!(total > 0 || self.total_ratio() == 0 || skip_mask == 0, SpinError::InsufficientReserves);
+        require!(total > 0 || self.total_ratio() == 0, SpinError::AllItemsPaused);
+        require!(total > 0, SpinError::ZeroSpinWeight);
+
+        let bound = u64::MAX - u64::MAX % total;
+        let mut nonce: u32 = 0;
+        let r = loop {
+            let draw = hash::hashv(&[&seed, &nonce.to_le_bytes()]).to_bytes();
+            let value = u64::from_le_bytes(draw[..8].try_into().unwrap());
+            if value < bound {
+                break value % total;
+            }
+            nonce += 1;
+        };
+
         let mut start = 0;
-        for (pos, item) in self.ratio_list.iter().enumerate() {
-            let end = start + item;
-            if r_pow >= start && r_pow < end {
+        for (pos, item) in active.iter().enumerate() {
+            let end = start + *item;
+            if r >= start && r < end {
                 self.last_spinindex = pos as u8;
-                return;
+                break;
             }
             start = end;
         }
+
+        Ok(())
     }
 }
 
@@ -195,11 +1311,13 @@ pub struct LatestUsers {
     pub reward_amount: [u64; MAX_LATEST_USER_COUNT],
     pub reward_mint: [Pubkey; MAX_LATEST_USER_COUNT],
     pub reward_type: [u8; MAX_LATEST_USER_COUNT],
+    // LATEST_FLAG_* bits
+    pub flags: [u8; MAX_LATEST_USER_COUNT],
 }
 
 
 impl LatestUsers {
-    pub fn push_front_last_user(&mut self, user: Pubkey, pay_amount: u64, reward_amount: u64, reward_mint: Pubkey, reward_type: u8) -> Result<()> {
+    pub fn push_front_last_user(&mut self, user: Pubkey, pay_amount: u64, reward_amount: u64, reward_mint: Pubkey, reward_type: u8, flags: u8) -> Result<()> {
         if self.count > 0 {
             for i in (0..self.count).rev() {
                 if i >= MAX_LATEST_USER_COUNT as u8 - 1 {
@@ -210,6 +1328,7 @@ impl LatestUsers {
                 self.reward_amount[(i + 1) as usize] = self.reward_amount[i as usize];
                 self.reward_mint[(i + 1) as usize] = self.reward_mint[i as usize];
                 self.reward_type[(i + 1) as usize] = self.reward_type[i as usize];
+                self.flags[(i + 1) as usize] = self.flags[i as usize];
             }
         }
 
@@ -218,9 +1337,629 @@ impl LatestUsers {
         self.reward_amount[0] = reward_amount;
         self.reward_mint[0] = reward_mint;
         self.reward_type[0] = reward_type;
+        self.flags[0] = flags;
 
-        self.count += 1;
+        if (self.count as usize) < MAX_LATEST_USER_COUNT {
+            self.count += 1;
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wheel(ratio_list: &[u32]) -> SpinItemList {
+        let mut items = SpinItemList::default();
+        for ratio in ratio_list {
+            items.add_spinitem(ItemRewardMints::default(), 2, *ratio, 0).unwrap();
+        }
+        items
+    }
+
+    fn draw(items: &mut SpinItemList, round: u32, skip_mask: u16) -> Result<u8> {
+        items.get_spinresult(hash::hashv(&[&round.to_le_bytes()]).to_bytes(), skip_mask)?;
+        Ok(items.last_spinindex)
+    }
+
+    fn draw_counts(items: &mut SpinItemList, rounds: u32, skip_mask: u16) -> [u32; SPIN_ITEM_COUNT] {
+        let mut counts = [0; SPIN_ITEM_COUNT];
+        for round in 0..rounds {
+            counts[draw(items, round, skip_mask).unwrap() as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn spinresult_is_deterministic_per_seed() {
+        let mut items = wheel(&[10, 20, 30, 40]);
+        for round in 0..50 {
+            assert_eq!(draw(&mut items, round, 0).unwrap(), draw(&mut items, round, 0).unwrap());
+        }
+    }
+
+    #[test]
+    fn spinresult_follows_the_weights() {
+        let mut items = wheel(&[1, 3]);
+        let counts = draw_counts(&mut items, 4000, 0);
+        assert_eq!(counts[0] + counts[1], 4000);
+        assert!((900..1100).contains(&counts[0]), "{:?}", counts);
+    }
+
+    #[test]
+    fn spinresult_never_draws_zero_weight_items() {
+        let mut items = wheel(&[0, 5, 0]);
+        assert_eq!(draw_counts(&mut items, 500, 0)[1], 500);
+    }
+
+    #[test]
+    fn spinresult_skips_paused_and_masked_items() {
+        let mut items = wheel(&[10, 10, 10]);
+        items.item_paused_list[0] = 1;
+        let counts = draw_counts(&mut items, 500, 1 << 2);
+        assert_eq!(counts[1], 500);
+    }
+
+    #[test]
+    fn spinresult_moves_sold_out_weight_to_the_fallback() {
+        let mut items = wheel(&[10, 10, 10]);
+        items.has_stock_limit_list[0] = 1;
+        items.fallback_index_list[0] = 2;
+        let counts = draw_counts(&mut items, 3000, 0);
+        assert_eq!(counts[0], 0);
+        assert!(counts[2] > counts[1] * 3 / 2, "{:?}", counts);
+    }
+
+    #[test]
+    fn spinresult_refuses_wheels_without_drawable_items() {
+        let mut items = wheel(&[10, 10]);
+        assert_spin_err(draw(&mut items, 0, 0b11), SpinError::InsufficientReserves);
+
+        items.item_paused_list = [1; SPIN_ITEM_COUNT];
+        assert_spin_err(draw(&mut items, 0, 0), SpinError::AllItemsPaused);
+
+        assert_spin_err(draw(&mut wheel(&[0, 0]), 0, 0), SpinError::ZeroSpinWeight);
+    }
+
+    fn rtp_pool() -> Pool {
+        Pool { ratio_total: 100, sol_price: 1000, max_rtp_bps: 9000, ..Default::default() }
+    }
+
+    fn sol_wheel(amount: u64) -> SpinItemList {
+        let mut items = wheel(&[50, 50]);
+        items.amount_list[0] = amount;
+        items
+    }
+
+    #[test]
+    fn validate_accepts_rtp_up_to_the_limit() {
+        let pay_tokens = PayTokenList::default();
+        sol_wheel(1000).validate(&rtp_pool(), &pay_tokens).unwrap();
+        sol_wheel(1800).validate(&rtp_pool(), &pay_tokens).unwrap();
+    }
+
+    #[test]
+    fn validate_refuses_rtp_above_the_limit() {
+        assert_spin_err(sol_wheel(1900).validate(&rtp_pool(), &PayTokenList::default()), SpinError::RtpTooHigh);
+    }
+
+    #[test]
+    fn validate_counts_the_jackpot_share() {
+        let pool = Pool { jackpot_bps: 4001, ..rtp_pool() };
+        assert_spin_err(sol_wheel(1000).validate(&pool, &PayTokenList::default()), SpinError::RtpTooHigh);
+    }
+
+    #[test]
+    fn validate_checks_the_deepest_bulk_discount() {
+        let mut pool = rtp_pool();
+        sol_wheel(1500).validate(&pool, &PayTokenList::default()).unwrap();
+
+        pool.set_bulk_discounts(&[5], &[2000]).unwrap();
+        assert_spin_err(sol_wheel(1500).validate(&pool, &PayTokenList::default()), SpinError::RtpTooHigh);
+    }
+
+    #[test]
+    fn validate_prices_token_rewards_in_mint_units() {
+        let mint = Pubkey::new_unique();
+        let mut pay_tokens = PayTokenList::default();
+        let pay_token = pay_tokens.get_or_add_pay_token(mint, 0).unwrap();
+        pay_token.price = 1_000_000_000;
+        pay_token.decimals = 6;
+        pay_token.is_enabled = true;
+
+        let mut items = wheel(&[50, 50]);
+        items.token_type_list[0] = 1;
+        items.reward_mint_list[0].add_reward_item(mint, 1_000_000);
+        items.validate(&rtp_pool(), &pay_tokens).unwrap();
+
+        items.reward_mint_list[0].amount_list[0] = 2_000_000;
+        assert_spin_err(items.validate(&rtp_pool(), &pay_tokens), SpinError::RtpTooHigh);
+    }
+
+    #[test]
+    fn validate_prices_rewards_in_other_currencies() {
+        let mint = Pubkey::new_unique();
+        let mut items = wheel(&[50, 50]);
+        items.token_type_list[0] = 1;
+        items.reward_mint_list[0].add_reward_item(mint, 2_000_000);
+        assert_spin_err(items.validate(&rtp_pool(), &PayTokenList::default()), SpinError::UnpricedReward);
+
+        // a disabled pay token still prices its mint for the SOL-priced spins
+        let mut pay_tokens = PayTokenList::default();
+        let pay_token = pay_tokens.get_or_add_pay_token(mint, 0).unwrap();
+        pay_token.price = 1_000_000_000;
+        pay_token.decimals = 6;
+        assert_spin_err(items.validate(&rtp_pool(), &pay_tokens), SpinError::RtpTooHigh);
+
+        items.reward_mint_list[0].amount_list[0] = 1_000_000;
+        items.amount_list[1] = 800;
+        items.validate(&rtp_pool(), &pay_tokens).unwrap();
+        items.amount_list[1] = 900;
+        assert_spin_err(items.validate(&rtp_pool(), &pay_tokens), SpinError::RtpTooHigh);
+    }
+
+    #[test]
+    fn add_spinitem_refuses_a_full_wheel() {
+        let mut items = wheel(&[1; SPIN_ITEM_COUNT]);
+        assert_spin_err(items.add_spinitem(ItemRewardMints::default(), 2, 1, 0), SpinError::CountOverflowAddItem);
+    }
+
+    #[test]
+    fn validate_refuses_weights_above_the_total() {
+        assert_spin_err(wheel(&[60, 50]).validate(&rtp_pool(), &PayTokenList::default()), SpinError::SpinWeightsOverflow);
+    }
+
+    #[test]
+    fn validate_refuses_stale_items_past_the_count() {
+        let mut items = wheel(&[50, 50]);
+        items.count = 1;
+        assert_spin_err(items.validate(&rtp_pool(), &PayTokenList::default()), SpinError::StaleSpinItem);
+    }
+
+    #[test]
+    fn pending_sol_reward_is_paid_once() {
+        let mut pending = UserPendingClaimState { is_sol: true, sol_amount: 7, ..Default::default() };
+        assert_eq!(pending.take_sol_reward().unwrap(), 7);
+        assert_spin_err(pending.take_sol_reward(), SpinError::RewardAlreadyClaimed);
+
+        assert_spin_err(UserPendingClaimState::default().take_sol_reward(), SpinError::InvalidReward);
+    }
+
+    #[test]
+    fn pending_token_reward_is_paid_once() {
+        let mint = Pubkey::new_unique();
+        let mut pending = UserPendingClaimState::default();
+        pending.add_item(mint, 5).unwrap();
+        pending.add_item(mint, 3).unwrap();
+
+        assert_eq!(pending.take_token_reward(&mint).unwrap(), 8);
+        assert_spin_err(pending.take_token_reward(&mint), SpinError::RewardAlreadyClaimed);
+        assert_spin_err(pending.take_token_reward(&Pubkey::new_unique()), SpinError::InvalidReward);
+    }
+
+    #[test]
+    fn pending_rewards_added_after_a_claim_are_kept_apart() {
+        let mint = Pubkey::new_unique();
+        let mut pending = UserPendingClaimState::default();
+        pending.add_item(mint, 5).unwrap();
+        pending.take_token_reward(&mint).unwrap();
+
+        pending.add_item(mint, 2).unwrap();
+        assert_eq!(pending.take_token_reward(&mint).unwrap(), 2);
+        assert_spin_err(pending.take_token_reward(&mint), SpinError::RewardAlreadyClaimed);
+    }
+
+    fn two_of_three() -> (Multisig, [Pubkey; 3]) {
+        let signer_list = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut multisig = Multisig::default();
+        multisig.set_signers(&signer_list, 2, true).unwrap();
+        (multisig, signer_list)
+    }
+
+    fn proposal(multisig: &Multisig) -> Proposal {
+        Proposal {
+            pool: Pubkey::default(),
+            proposer: Pubkey::default(),
+            proposal_id: 0,
+            version: multisig.version,
+            action: MultisigAction::RenounceSuperadmin,
+            approval_mask: 0,
+            approval_count: 0,
+        }
+    }
+
+    #[test]
+    fn multisig_requires_the_threshold() {
+        let (multisig, signer_list) = two_of_three();
+        let mut proposal = proposal(&multisig);
+
+        proposal.approve(&multisig, &signer_list[0]).unwrap();
+        assert_spin_err(multisig.require_approved(&proposal), SpinError::ProposalThresholdNotMet);
+
+        proposal.approve(&multisig, &signer_list[2]).unwrap();
+        multisig.require_approved(&proposal).unwrap();
+    }
+
+    #[test]
+    fn multisig_counts_each_signer_once() {
+        let (multisig, signer_list) = two_of_three();
+        let mut proposal = proposal(&multisig);
+
+        proposal.approve(&multisig, &signer_list[0]).unwrap();
+        assert_spin_err(proposal.approve(&multisig, &signer_list[0]), SpinError::ProposalAlreadyApproved);
+        assert_spin_err(proposal.approve(&multisig, &Pubkey::new_unique()), SpinError::IncorrectMultisigSigner);
+        assert_eq!(proposal.approval_count, 1);
+    }
+
+    #[test]
+    fn multisig_signer_changes_void_open_proposals() {
+        let (mut multisig, signer_list) = two_of_three();
+        let mut proposal = proposal(&multisig);
+        proposal.approve(&multisig, &signer_list[0]).unwrap();
+        proposal.approve(&multisig, &signer_list[1]).unwrap();
+
+        multisig.set_signers(&signer_list, 2, true).unwrap();
+        assert_spin_err(multisig.require_approved(&proposal), SpinError::StaleProposal);
+        assert_spin_err(proposal.approve(&multisig, &signer_list[2]), SpinError::StaleProposal);
+    }
+
+    #[test]
+    fn multisig_refuses_unreachable_thresholds() {
+        let signer_list = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut multisig = Multisig::default();
+        assert_spin_err(multisig.set_signers(&signer_list, 3, true), SpinError::InvalidMultisig);
+        assert_spin_err(multisig.set_signers(&signer_list, 0, true), SpinError::InvalidMultisig);
+        assert_spin_err(multisig.set_signers(&[signer_list[0], signer_list[0]], 1, true), SpinError::InvalidMultisig);
+
+        multisig.set_signers(&signer_list, 0, false).unwrap();
+        assert_spin_err(multisig.require_approved(&proposal(&multisig)), SpinError::MultisigDisabled);
+    }
+
+    fn pending_config() -> PendingWheelConfig {
+        PendingWheelConfig {
+            items: SpinItemList::default(),
+            sol_price: 0,
+            price_mint_list: [Pubkey::default(); MAX_PAY_TOKEN_COUNT],
+            price_list: [0; MAX_PAY_TOKEN_COUNT],
+            price_count: 0,
+            activates_at: 0,
+            is_staged: 0,
+            ratio_total: 0,
+            max_rtp_bps: 0,
+            jackpot_bps: 0,
+            restock_list: [0; SPIN_ITEM_COUNT],
+            is_restock_list: [0; SPIN_ITEM_COUNT],
+            is_enabled_list: [0; MAX_PAY_TOKEN_COUNT],
+            discount_min_count_list: [0; MAX_DISCOUNT_TIER_COUNT],
+            discount_bps_list: [0; MAX_DISCOUNT_TIER_COUNT],
+            discount_tier_count: 0,
+            config_delay: 0,
+        }
+    }
+
+    #[test]
+    fn pending_config_applies_only_when_staged() {
+        let mut pool = rtp_pool();
+        let mut pay_tokens = PayTokenList::default();
+        let mut state = wheel(&[50, 50]);
+        assert_spin_err(pending_config().apply(&mut state, &mut pool, &mut pay_tokens), SpinError::ConfigNotStaged);
+    }
+
+    #[test]
+    fn pending_config_apply_keeps_live_stock() {
+        let mut pool = rtp_pool();
+        let mut pay_tokens = PayTokenList::default();
+        let mut state = wheel(&[50, 50]);
+        state.has_stock_limit_list = [1; SPIN_ITEM_COUNT];
+        state.stock_list[0] = 5;
+        state.stock_list[1] = 5;
+
+        let mut pending = pending_config();
+        pending.stage_from(&state, &pool, &pay_tokens);
+        pending.items.ratio_list[0] = 30;
+        pending.items.ratio_list[1] = 70;
+        pending.sol_price = 2000;
+        pending.restock_list[1] = 9;
+        pending.is_restock_list[1] = 1;
+
+        // spins drawn while the change waits out its delay
+        state.stock_list[0] = 4;
+        state.stock_list[1] = 3;
+
+        pending.apply(&mut state, &mut pool, &mut pay_tokens).unwrap();
+        let ratio_list = state.ratio_list;
+        let stock_list = state.stock_list;
+        assert_eq!(ratio_list[..2], [30, 70]);
+        assert_eq!(stock_list[..2], [4, 9]);
+        assert_eq!(pool.sol_price, 2000);
+        assert_eq!(pending.is_staged, 0);
+    }
+
+    #[test]
+    fn pending_config_stages_pay_tokens_discounts_and_delay() {
+        let mut pool = Pool { config_delay: DAY, ..rtp_pool() };
+        let (mint, new_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pay_tokens = PayTokenList::default();
+        let pay_token = pay_tokens.get_or_add_pay_token(mint, 0).unwrap();
+        pay_token.price = 1000;
+        pay_token.is_enabled = true;
+        let mut state = wheel(&[50, 50]);
+
+        let mut pending = pending_config();
+        pending.stage_from(&state, &pool, &pay_tokens);
+        pending.set_pay_token(&mint, 2000, false).unwrap();
+        // added to the live list after staging, disabled and unpriced until activation
+        pay_tokens.get_or_add_pay_token(new_mint, 0).unwrap();
+        pending.set_pay_token(&new_mint, 500, true).unwrap();
+        pending.set_bulk_discounts(&[5], &[1000]).unwrap();
+        pending.config_delay = 0;
+        assert_spin_err(pending.set_bulk_discounts(&[1], &[1000]), SpinError::InvalidBulkDiscount);
+
+        pending.apply(&mut state, &mut pool, &mut pay_tokens).unwrap();
+        let old_token = pay_tokens.find_pay_token(&mint).unwrap();
+        assert_eq!((old_token.price, old_token.is_enabled), (2000, false));
+        let new_token = pay_tokens.get_pay_token(&new_mint).unwrap();
+        assert_eq!(new_token.price, 500);
+        assert_eq!(pool.max_discount_bps(), 1000);
+        assert_eq!(pool.config_delay, 0);
+    }
+
+    #[test]
+    fn config_delay_increases_apply_at_once() {
+        let mut pool = Pool { config_delay: DAY, ..rtp_pool() };
+        let pay_tokens = PayTokenList::default();
+        let state = wheel(&[50, 50]);
+        let mut pending = pending_config();
+        pending.stage_from(&state, &pool, &pay_tokens);
+
+        set_config_delay_checked(&mut pending, &state, &mut pool, &pay_tokens, 2 * DAY).unwrap();
+        let staged_delay = pending.config_delay;
+        assert_eq!(pool.config_delay, 2 * DAY);
+        assert_eq!(staged_delay, 2 * DAY);
+        assert_spin_err(set_config_delay_checked(&mut pending, &state, &mut pool, &pay_tokens, -1), SpinError::ConfigTimelocked);
+    }
+
+    #[test]
+    fn pending_config_apply_keeps_live_pauses() {
+        let mut pool = rtp_pool();
+        let mut pay_tokens = PayTokenList::default();
+        let mut state = wheel(&[50, 25, 25]);
+        state.item_paused_list[0] = 1;
+
+        let mut pending = pending_config();
+        pending.stage_from(&state, &pool, &pay_tokens);
+        pending.items.set_count(2).unwrap();
+        pending.items.ratio_list[1] = 50;
+
+        // paused and unpaused while the change waits out its delay
+        state.item_paused_list[0] = 0;
+        state.item_paused_list[1] = 1;
+        state.item_paused_list[2] = 1;
+
+        pending.apply(&mut state, &mut pool, &mut pay_tokens).unwrap();
+        let item_paused_list = state.item_paused_list;
+        assert_eq!(item_paused_list[..3], [0, 1, 0]);
+    }
+
+    #[test]
+    fn pending_config_apply_revalidates() {
+        let mut pool = rtp_pool();
+        let mut pay_tokens = PayTokenList::default();
+        let mut state = wheel(&[50, 50]);
+
+        let mut pending = pending_config();
+        pending.stage_from(&state, &pool, &pay_tokens);
+        pending.items.amount_list[0] = 1000;
+        pending.max_rtp_bps = 1000;
+
+        assert_spin_err(pending.apply(&mut state, &mut pool, &mut pay_tokens), SpinError::RtpTooHigh);
+        assert_eq!(pool.max_rtp_bps, 9000);
+        assert_eq!(pending.is_staged, 1);
+    }
+
+    fn liabilities() -> Liabilities {
+        Liabilities {
+            sol_amount: 0,
+            mint_list: [Pubkey::default(); MAX_REWARD_TOKEN_COUNT],
+            amount_list: [0; MAX_REWARD_TOKEN_COUNT],
+            count: 0,
+        }
+    }
+
+    #[test]
+    fn liabilities_release_only_unclaimed_rewards() {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pending = UserPendingClaimState { is_sol: true, sol_amount: 7, ..Default::default() };
+        pending.add_item(mint_a, 5).unwrap();
+        pending.add_item(mint_b, 3).unwrap();
+
+        let mut liabilities = liabilities();
+        liabilities.add_sol(10).unwrap();
+        liabilities.add(&mint_a, 5).unwrap();
+        liabilities.add(&mint_b, 4).unwrap();
+
+        pending.take_token_reward(&mint_a).unwrap();
+        liabilities.sub(&mint_a, 5);
+        liabilities.release(&pending);
+
+        let sol_amount = liabilities.sol_amount;
+        assert_eq!(sol_amount, 3);
+        assert_eq!(liabilities.get(&mint_a), 0);
+        assert_eq!(liabilities.get(&mint_b), 1);
+    }
+
+    #[test]
+    fn item_list_sizes_match_the_space_comments() {
+        assert_eq!(std::mem::size_of::<ItemRewardMints>(), 32 * 10 + 8 * 10 + 1);
+        assert_eq!(std::mem::size_of::<SpinItemList>(), 6317);
+    }
+
+    #[test]
+    fn bulk_price_takes_the_best_matching_tier() {
+        let mut pool = Pool::default();
+        pool.set_bulk_discounts(&[5, 10], &[1000, 2000]).unwrap();
+
+        assert_eq!(pool.bulk_price(100, 1).unwrap(), 100);
+        assert_eq!(pool.bulk_price(100, 4).unwrap(), 400);
+        assert_eq!(pool.bulk_price(100, 5).unwrap(), 450);
+        assert_eq!(pool.bulk_price(100, 10).unwrap(), 800);
+    }
+
+    #[test]
+    fn bulk_price_refuses_bad_counts_and_overflows() {
+        let pool = Pool::default();
+        assert_spin_err(pool.bulk_price(100, 0), SpinError::InvalidSpinCount);
+        assert_spin_err(pool.bulk_price(100, MAX_SPIN_COUNT + 1), SpinError::InvalidSpinCount);
+        assert_spin_err(pool.bulk_price(u64::MAX, 2), SpinError::AmountOverflow);
+    }
+
+    #[test]
+    fn credits_are_kept_per_pool_and_mint() {
+        let (pool_a, pool_b, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut user_state = UserState::default();
+        user_state.add_credit(&pool_a, &mint, 100).unwrap();
+        user_state.add_credit(&pool_a, &mint, 50).unwrap();
+        user_state.add_credit(&pool_b, &SOL_PAY_MINT, 30).unwrap();
+
+        assert_eq!(user_state.credit_of(&pool_a, &mint), 150);
+        assert_eq!(user_state.credit_of(&pool_b, &mint), 0);
+        assert_eq!(user_state.credit_of(&pool_b, &SOL_PAY_MINT), 30);
+        assert_eq!(user_state.credit_count, 2);
+    }
+
+    #[test]
+    fn credits_cannot_be_overspent() {
+        let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut user_state = UserState::default();
+        user_state.add_credit(&pool, &mint, 100).unwrap();
+
+        assert_spin_err(user_state.spend_credit(&pool, &mint, 101), SpinError::InsufficientCredits);
+        assert_spin_err(user_state.spend_credit(&pool, &SOL_PAY_MINT, 1), SpinError::InsufficientCredits);
+        user_state.spend_credit(&pool, &mint, 60).unwrap();
+        assert_eq!(user_state.credit_of(&pool, &mint), 40);
+        assert_spin_err(user_state.add_credit(&pool, &mint, u64::MAX), SpinError::AmountOverflow);
+    }
+
+    #[test]
+    fn emptied_credits_free_their_slot() {
+        let pool = Pubkey::new_unique();
+        let mint_list: Vec<Pubkey> = (0..MAX_CREDIT_COUNT).map(|_| Pubkey::new_unique()).collect();
+        let mut user_state = UserState::default();
+        for mint in mint_list.iter() {
+            user_state.add_credit(&pool, mint, 10).unwrap();
+        }
+        assert_spin_err(user_state.add_credit(&pool, &Pubkey::new_unique(), 10), SpinError::CountOverflowAddItem);
+
+        user_state.spend_credit(&pool, &mint_list[0], 10).unwrap();
+        assert_eq!(user_state.credit_count as usize, MAX_CREDIT_COUNT - 1);
+        assert_eq!(user_state.credit_of(&pool, &mint_list[MAX_CREDIT_COUNT - 1]), 10);
+        user_state.add_credit(&pool, &Pubkey::new_unique(), 10).unwrap();
+    }
+
+    const DAY: i64 = 86400;
+
+    #[test]
+    fn free_spin_waits_out_the_period() {
+        let pool = Pubkey::new_unique();
+        let mut user_state = UserState::default();
+        user_state.take_free_spin(&pool, 1000, DAY).unwrap();
+
+        assert_spin_err(user_state.take_free_spin(&pool, 1000 + DAY - 1, DAY), SpinError::FreeSpinCooldown);
+        user_state.take_free_spin(&pool, 1000 + DAY, DAY).unwrap();
+    }
+
+    #[test]
+    fn free_spin_cooldowns_are_per_wheel() {
+        let (pool_a, pool_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut user_state = UserState::default();
+        user_state.take_free_spin(&pool_a, 1000, DAY).unwrap();
+        user_state.take_free_spin(&pool_b, 1000, DAY).unwrap();
+
+        assert_spin_err(user_state.take_free_spin(&pool_b, 2000, DAY), SpinError::FreeSpinCooldown);
+    }
+
+    #[test]
+    fn free_spin_refuses_disabled_and_overflowing_periods() {
+        let pool = Pubkey::new_unique();
+        let mut user_state = UserState::default();
+        assert_spin_err(user_state.take_free_spin(&pool, 1000, 0), SpinError::FreeSpinDisabled);
+        assert_spin_err(user_state.take_free_spin(&pool, 1000, -DAY), SpinError::FreeSpinDisabled);
+        assert_spin_err(user_state.take_free_spin(&pool, 1000, i64::MAX), SpinError::AmountOverflow);
+    }
+
+    #[test]
+    fn free_spin_reuses_finished_cooldowns() {
+        let mut user_state = UserState::default();
+        for i in 0..MAX_FREE_SPIN_WHEEL_COUNT as i64 {
+            user_state.take_free_spin(&Pubkey::new_unique(), 1000 + i, DAY).unwrap();
+        }
+        let pool = Pubkey::new_unique();
+        assert_spin_err(user_state.take_free_spin(&pool, 2000, DAY), SpinError::FreeSpinCooldownListFull);
+
+        user_state.take_free_spin(&pool, 1000 + DAY, DAY).unwrap();
+        assert_spin_err(user_state.take_free_spin(&pool, 1000 + DAY, DAY), SpinError::FreeSpinCooldown);
+    }
+
+    #[test]
+    fn validate_items_bounds_the_reward_mints_of_a_bulk_claim() {
+        let shared = Pubkey::new_unique();
+        let mut items = SpinItemList::default();
+        for _ in 0..4 {
+            let mut reward_mints = ItemRewardMints::default();
+            reward_mints.add_reward_item(shared, 1);
+            items.add_spinitem(reward_mints, 1, 10, 0).unwrap();
+        }
+        items.validate_items(&rtp_pool()).unwrap();
+
+        let mut items = SpinItemList::default();
+        for _ in 0..4 {
+            let mut reward_mints = ItemRewardMints::default();
+            for _ in 0..REWARD_TOKEN_COUNT_PER_ITEM {
+                reward_mints.add_reward_item(Pubkey::new_unique(), 1);
+            }
+            items.add_spinitem(reward_mints, 1, 10, 0).unwrap();
+        }
+        assert_spin_err(items.validate_items(&rtp_pool()), SpinError::TooManyRewardMints);
+    }
+
+    #[test]
+    fn accept_superadmin_needs_the_pending_key() {
+        let (superadmin, new_superadmin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool = Pool { superadmin, ..Default::default() };
+        let mut admin_info = AdminInfo::default();
+        assert_spin_err(pool.accept_superadmin(&mut admin_info, Pubkey::default()), SpinError::IncorrectSuperAdminOrAdmin);
+
+        pool.pending_superadmin = new_superadmin;
+        assert_spin_err(pool.accept_superadmin(&mut admin_info, Pubkey::new_unique()), SpinError::IncorrectSuperAdminOrAdmin);
+        assert_eq!(pool.superadmin, superadmin);
+    }
+
+    #[test]
+    fn accept_superadmin_migrates_admin_entries() {
+        let (superadmin, new_superadmin, admin) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool = Pool { superadmin, pending_superadmin: new_superadmin, ..Default::default() };
+        let mut admin_info = AdminInfo::default();
+        admin_info.add_admin(new_superadmin, ROLE_PAUSER).unwrap();
+        admin_info.add_admin(superadmin, ROLE_TREASURER).unwrap();
+        admin_info.add_admin(admin, ROLE_ODDS_MANAGER).unwrap();
+
+        pool.accept_superadmin(&mut admin_info, new_superadmin).unwrap();
+        assert_eq!(pool.superadmin, new_superadmin);
+        assert_eq!(pool.pending_superadmin, Pubkey::default());
+        // the new superadmin's entry is dropped, the outgoing one keeps only its own entry
+        assert!(!admin_info.is_admin(&new_superadmin));
+        assert_eq!(admin_info.roles_of(&superadmin), ROLE_TREASURER);
+        assert_eq!(admin_info.roles_of(&admin), ROLE_ODDS_MANAGER);
+    }
+
+    #[test]
+    fn renounce_superadmin_drops_a_pending_transfer() {
+        let new_superadmin = Pubkey::new_unique();
+        let mut pool = Pool { superadmin: Pubkey::new_unique(), pending_superadmin: new_superadmin, ..Default::default() };
+        pool.renounce_superadmin();
+        assert_eq!(pool.superadmin, Pubkey::default());
+        assert_spin_err(pool.accept_superadmin(&mut AdminInfo::default(), new_superadmin), SpinError::IncorrectSuperAdminOrAdmin);
+    }
+}
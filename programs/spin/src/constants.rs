@@ -1,3 +1,4 @@
+use anchor_lang::prelude::*;
 
 pub const DEV_WALLET_KEY: &str = "74abUnzELqrKsJip68xVQdvduUUzQanjsdoN6jnRzqfp";
 
@@ -7,12 +8,76 @@ pub const USER_STATE_SEED: &[u8] = b"USER_STATE_SEED";
 pub const ADMIN_LIST_SEED: &[u8] = b"ADMIN_LIST_SEED";
 pub const VAULT_SEED: &[u8] = b"SOL_VAULT";
 pub const LAST_USERS_SEED: &str = "LAST_USERS_SEED";
+pub const SPIN_ITEMS_SEED: &[u8] = b"SPIN_ITEMS_SEED";
+pub const FREE_SPIN_ITEMS_SEED: &[u8] = b"FREE_SPIN_ITEMS_SEED";
+pub const PAY_TOKEN_LIST_SEED: &[u8] = b"PAY_TOKEN_LIST_SEED";
+pub const TREASURY_SEED: &[u8] = b"TREASURY_SEED";
+pub const MULTISIG_SEED: &[u8] = b"MULTISIG_SEED";
+pub const PROPOSAL_SEED: &[u8] = b"PROPOSAL_SEED";
+pub const PENDING_CONFIG_SEED: &[u8] = b"PENDING_CONFIG_SEED";
+pub const LIABILITIES_SEED: &[u8] = b"LIABILITIES_SEED";
+pub const JACKPOT_SEED: &[u8] = b"JACKPOT_SEED";
+pub const JACKPOT_VAULT_SEED: &[u8] = b"JACKPOT_VAULT_SEED";
+pub const SPIN_REQUEST_SEED: &[u8] = b"SPIN_REQUEST_SEED";
+pub const PENDING_CLAIM_SEED: &[u8] = b"PENDING_CLAIM_SEED";
+pub const TICKET_MINT_SEED: &[u8] = b"TICKET_MINT_SEED";
+pub const RANDOMNESS_REQUEST_SEED: &[u8] = b"RANDOMNESS_REQUEST_SEED";
 
 
 pub const SPIN_ITEM_COUNT: usize = 15;
+// fallback index of a limited item whose weight is spread over the rest when sold out
+pub const NO_FALLBACK_ITEM: u8 = u8::MAX;
+// token_type of an item paying its amount, in bps, of the accrued jackpot
+pub const TOKEN_TYPE_JACKPOT: u8 = 3;
+
+// LatestUsers.flags bits
+pub const LATEST_FLAG_JACKPOT: u8 = 1 << 0;
+pub const LATEST_FLAG_TICKET: u8 = 1 << 1;
+pub const LATEST_FLAG_FREE: u8 = 1 << 2;
 pub const REWARD_TOKEN_COUNT_PER_ITEM: usize = 10;
 pub const ADMIN_MAX_COUNT: usize = 15;
+
+// admin roles, stored as a bitmask per AdminInfo entry
+pub const ROLE_ODDS_MANAGER: u8 = 1 << 0;
+pub const ROLE_PRICING_MANAGER: u8 = 1 << 1;
+pub const ROLE_TREASURER: u8 = 1 << 2;
+pub const ROLE_PAUSER: u8 = 1 << 3;
+pub const ROLE_ADMIN_MANAGER: u8 = 1 << 4;
+pub const ROLE_PROMOTER: u8 = 1 << 5;
+// Pool.pause_flags bits
+pub const PAUSE_SOL_SPIN: u8 = 1 << 0;
+pub const PAUSE_TOKEN_SPIN: u8 = 1 << 1;
+pub const PAUSE_CLAIM: u8 = 1 << 2;
+pub const PAUSE_WITHDRAW: u8 = 1 << 3;
+pub const PAUSE_TICKET_SPIN: u8 = 1 << 4;
+pub const PAUSE_FREE_SPIN: u8 = 1 << 5;
+pub const PAUSE_ALL: u8 = PAUSE_SOL_SPIN | PAUSE_TOKEN_SPIN | PAUSE_CLAIM | PAUSE_WITHDRAW | PAUSE_TICKET_SPIN | PAUSE_FREE_SPIN;
+
+pub const ROLE_ALL: u8 = ROLE_ODDS_MANAGER | ROLE_PRICING_MANAGER | ROLE_TREASURER | ROLE_PAUSER | ROLE_ADMIN_MANAGER | ROLE_PROMOTER;
 pub const MAX_LATEST_USER_COUNT: usize = 10;
+pub const MAX_ORACLE_COUNT: usize = 5;
+pub const MAX_PAY_TOKEN_COUNT: usize = 10;
+pub const MAX_SPLIT_RECIPIENT_COUNT: usize = 4;
+pub const MAX_DISCOUNT_TIER_COUNT: usize = 3;
+pub const MAX_SPIN_COUNT: u8 = 10;
+// a bulk spin merges its results per mint, so one pending account holds many results
+pub const MAX_PENDING_REWARD_COUNT: usize = 30;
+pub const MAX_CREDIT_COUNT: usize = 8;
+pub const MAX_FREE_SPIN_WHEEL_COUNT: usize = 8;
+pub const MAX_TREASURY_DEST_COUNT: usize = 5;
+pub const MAX_TREASURY_CAP_COUNT: usize = 11; // SOL + MAX_PAY_TOKEN_COUNT
+pub const MAX_MULTISIG_SIGNER_COUNT: usize = 10;
 pub const MAX_REWARD_TOKEN_COUNT: usize = 150; // REWARD_TOKEN_COUNT_PER_ITEM * SPIN_ITEM_COUNT;
 
 pub const REWARD_TOKEN_DECIMAL: u8 = 9;
+
+// pay mint passed to request_spin for SOL payments
+pub const SOL_PAY_MINT: Pubkey = spl_token::native_mint::ID;
+
+pub const BPS_DENOMINATOR: u64 = 10000;
+pub const DEFAULT_RATIO_TOTAL: u64 = 100000;
+
+// settle_spin mixes in the hash of this many slots after request_spin
+pub const SPIN_SETTLE_DELAY_SLOTS: u64 = 2;
+// SlotHashes keeps this many entries, older target slots can't be settled anymore
+pub const SLOT_HASHES_MAX_AGE: u64 = 512;
@@ -20,9 +20,195 @@ pub enum SpinError {
     #[msg("Invalid Reward")]
     InvalidReward,
 
-    #[msg("Invalid Dust Mint Key")]
-    IncorreectDustMintKey,
+    #[msg("Invalid Pay Mint Key")]
+    IncorrectPayMint,
 
-    #[msg("Invalid Forge Mint Key")]
-    IncorreectForgeMintKey,
-}
\ No newline at end of file
+    #[msg("Pay Token Is Disabled")]
+    PayTokenDisabled,
+
+    #[msg("Revealed Seed Does Not Match Commitment")]
+    InvalidSpinCommitment,
+
+    #[msg("Spin Is Not Ready To Settle")]
+    SpinNotReady,
+
+    #[msg("Spin Request Expired")]
+    SpinRequestExpired,
+
+    #[msg("Spin Request Has Not Expired")]
+    SpinNotExpired,
+
+    #[msg("Spin Request Uses Another Randomness Source")]
+    IncorrectRandomnessSource,
+
+    #[msg("Incorrect Oracle")]
+    IncorrectOracle,
+
+    #[msg("Invalid Oracle Proof")]
+    InvalidOracleProof,
+
+    #[msg("Oracle List Is Full")]
+    OracleListFull,
+
+    #[msg("Oracle Already Exists")]
+    OracleAlreadyExists,
+
+    #[msg("Oracle Not Found")]
+    OracleNotFound,
+
+    #[msg("Amount Overflow")]
+    AmountOverflow,
+
+    #[msg("Randomness Already Fulfilled")]
+    RandomnessAlreadyFulfilled,
+
+    #[msg("Randomness Not Fulfilled")]
+    RandomnessNotFulfilled,
+
+    #[msg("Spin Items Have Zero Total Weight")]
+    ZeroSpinWeight,
+
+    #[msg("Spin Item Weights Exceed Configured Total")]
+    SpinWeightsOverflow,
+
+    #[msg("Spin Item Weights Do Not Add Up To Configured Total")]
+    SpinWeightsIncomplete,
+
+    #[msg("Unused Spin Item Carries Weight")]
+    StaleSpinItem,
+
+    #[msg("Return To Player Exceeds Ceiling")]
+    RtpTooHigh,
+
+    #[msg("Fee Rate Exceeds 100%")]
+    InvalidFeeRate,
+
+    #[msg("Incorrect Revenue Split Recipient")]
+    IncorrectSplitRecipient,
+
+    #[msg("Invalid Revenue Split")]
+    InvalidRevenueSplit,
+
+    #[msg("Reward Already Claimed")]
+    RewardAlreadyClaimed,
+
+    #[msg("Reward Vault Is Not The Pool's Account For This Mint")]
+    IncorrectRewardVault,
+
+    #[msg("Withdraw Destination Is Not Allowlisted")]
+    IncorrectWithdrawDestination,
+
+    #[msg("Withdrawal Exceeds Epoch Cap")]
+    WithdrawalCapExceeded,
+
+    #[msg("Invalid Admin Role")]
+    InvalidRole,
+
+    #[msg("Admin Already Exists")]
+    AdminAlreadyExists,
+
+    #[msg("Admin Not Found")]
+    AdminNotFound,
+
+    #[msg("Action Requires A Multisig Proposal")]
+    MultisigRequired,
+
+    #[msg("Multisig Is Not Enabled")]
+    MultisigDisabled,
+
+    #[msg("Invalid Multisig Signers Or Threshold")]
+    InvalidMultisig,
+
+    #[msg("Signer Is Not A Multisig Member")]
+    IncorrectMultisigSigner,
+
+    #[msg("Proposal Already Approved By Signer")]
+    ProposalAlreadyApproved,
+
+    #[msg("Proposal Has Not Reached Threshold")]
+    ProposalThresholdNotMet,
+
+    #[msg("Proposal Was Created For Old Multisig Signers")]
+    StaleProposal,
+
+    #[msg("Proposal Action Does Not Match Instruction")]
+    IncorrectProposalAction,
+
+    #[msg("Wheel Config Changes Must Be Staged")]
+    ConfigTimelocked,
+
+    #[msg("No Wheel Config Is Staged")]
+    ConfigNotStaged,
+
+    #[msg("Staged Wheel Config Is Not Active Yet")]
+    ConfigNotReady,
+
+    #[msg("Invalid Pause Flags")]
+    InvalidPauseFlags,
+
+    #[msg("SOL Spins Are Paused For Maintenance")]
+    SolSpinPaused,
+
+    #[msg("Token Spins Are Paused For Maintenance")]
+    TokenSpinPaused,
+
+    #[msg("Claims Are Paused For Maintenance")]
+    ClaimPaused,
+
+    #[msg("Withdrawals Are Paused For Maintenance")]
+    WithdrawPaused,
+
+    #[msg("Vault Reserves Do Not Cover Liabilities")]
+    InsufficientReserves,
+
+    #[msg("All Spin Items Are Paused")]
+    AllItemsPaused,
+
+    #[msg("Invalid Fallback Spin Item")]
+    InvalidFallbackItem,
+
+    #[msg("Jackpot Payout Must Be At Most 10000 Bps")]
+    InvalidJackpotPayout,
+
+    #[msg("Invalid Spin Count")]
+    InvalidSpinCount,
+
+    #[msg("Invalid Bulk Discount Tiers")]
+    InvalidBulkDiscount,
+
+    #[msg("Insufficient Spin Credits")]
+    InsufficientCredits,
+
+    #[msg("Ticket Spins Are Paused For Maintenance")]
+    TicketSpinPaused,
+
+    #[msg("Free Spins Are Paused For Maintenance")]
+    FreeSpinPaused,
+
+    #[msg("Free Spins Are Disabled")]
+    FreeSpinDisabled,
+
+    #[msg("Free Spin Is Still Cooling Down")]
+    FreeSpinCooldown,
+
+    #[msg("Free Spin Period Must Not Be Negative")]
+    InvalidFreeSpinPeriod,
+
+    #[msg("Too Many Free Spin Cooldowns Running")]
+    FreeSpinCooldownListFull,
+
+    #[msg("Wheel Pays More Reward Mints Than A Claim Can Hold")]
+    TooManyRewardMints,
+
+    #[msg("Reward Has No Price To Bound The RTP With")]
+    UnpricedReward,
+}
+
+#[cfg(test)]
+pub fn assert_spin_err<T>(result: Result<T>, expected: SpinError) {
+    match result {
+        Err(Error::AnchorError(err)) => assert_eq!(err.error_name, expected.name()),
+        Err(err) => panic!("expected {}, got {}", expected.name(), err),
+        Ok(_) => panic!("expected {}, got Ok", expected.name()),
+    }
+}
@@ -1,9 +1,14 @@
+// Anchor pins every handler's error type to anchor_lang::error::Error, so boxing it is not ours to do.
+#![allow(clippy::result_large_err)]
+
 use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
 use anchor_spl::{
     associated_token::AssociatedToken,
 };
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-use solana_program::{program::invoke, program::invoke_signed, system_instruction};
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use solana_program::{hash, program::invoke, program::invoke_signed, system_instruction, sysvar};
+use solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
 use std::mem::size_of;
 
 pub mod account;
@@ -24,491 +29,2497 @@ pub mod spin {
 
     pub fn initialize(
         ctx: Context<Initialize>,
+        wheel_id: u64,
     ) -> Result<()> {
         msg!("initialize");
 
         let pool = &mut ctx.accounts.pool;
+        pool.wheel_id = wheel_id;
         pool.superadmin = ctx.accounts.super_admin.key();
-        pool.dev_wallet = Pubkey::try_from(DEV_WALLET_KEY).unwrap();
-        pool.dev_fee = 3; // means 3%
+        pool.set_revenue_split(&[Pubkey::try_from(DEV_WALLET_KEY).unwrap()], &[BPS_DENOMINATOR])?;
+        pool.sol_fee_bps = 300; // means 3%
+        pool.ratio_total = DEFAULT_RATIO_TOTAL;
+        pool.max_rtp_bps = BPS_DENOMINATOR;
 
         let mut _state = ctx.accounts.state.load_init()?;
+        let mut _pending_config = ctx.accounts.pending_config.load_init()?;
+        let mut _liabilities = ctx.accounts.liabilities.load_init()?;
+
+        // jackpot cuts are usually below the rent minimum
+        fund_rent_exempt(
+            &ctx.accounts.initializer,
+            &ctx.accounts.jackpot_vault,
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
 
         Ok(())
     }
 
     pub fn set_pay_info(
         ctx: Context<SetPayInfo>,
+        _wheel_id: u64,
         sol_price: u64,
-        dust_price: u64,
-        forge_price: u64,
-        dev_fee: u64,
-        dev_wallet: Pubkey,
-        is_use_paytoken: bool,
     ) -> Result<()> {
-        msg!("initialize");
+        msg!("set_pay_info");
         let accts = ctx.accounts;
         let pool = &mut accts.pool;
 
-        let mut is_admin = false;
-        for i in 0..accts.admin_info.count {
-            if accts.admin_info.admin_list[i as usize].eq(&accts.admin.key()) {
-                is_admin = true;
-                break;
-            }
+        require_role(pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(pool.config_delay == 0, SpinError::ConfigTimelocked);
+
+        pool.sol_price = sol_price;
+
+        // new prices change the return-to-player of the current wheel
+        accts.state.load()?.validate(pool, &accts.pay_token_list)?;
+
+        Ok(())
+    }
+
+    pub fn set_revenue_split(
+        ctx: Context<SetRevenueSplit>,
+        _wheel_id: u64,
+        sol_fee_bps: u64,
+        recipient_list: Vec<Pubkey>,
+        share_list: Vec<u64>,
+    ) -> Result<()> {
+        msg!("set_revenue_split");
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        let pool = &mut ctx.accounts.pool;
+
+        require!(sol_fee_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+        pool.sol_fee_bps = sol_fee_bps;
+        pool.set_revenue_split(&recipient_list, &share_list)?;
+
+        Ok(())
+    }
+
+    pub fn set_jackpot(ctx: Context<SetJackpot>, _wheel_id: u64, jackpot_bps: u64) -> Result<()> {
+        msg!("set_jackpot");
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(jackpot_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+        // wheels created before the jackpot vault was funded on initialize
+        fund_rent_exempt(
+            &accts.superadmin.to_account_info(),
+            &accts.jackpot_vault,
+            &accts.system_program.to_account_info(),
+        )?;
+
+        // the jackpot share counts towards the RTP, so it follows the timelock
+        if accts.pool.config_delay > 0 {
+            return stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                pending.jackpot_bps = jackpot_bps;
+                Ok(())
+            });
         }
+        accts.pool.jackpot_bps = jackpot_bps;
+        accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
 
-        require!(is_admin || pool.superadmin.eq(&accts.admin.key()),
-            SpinError::IncorrectSuperAdminOrAdmin
-        );
+        Ok(())
+    }
 
-        if is_use_paytoken == true {
-            pool.dust_mint = accts.dust_mint.key();
-            pool.forge_mint = accts.forge_mint.key();
+    pub fn set_pay_token(
+        ctx: Context<SetPayToken>,
+        _wheel_id: u64,
+        price: u64,
+        fee_bps: u64,
+        is_enabled: bool,
+    ) -> Result<()> {
+        msg!("set_pay_token");
+        let accts = ctx.accounts;
+        let pool = &accts.pool;
+        let is_superadmin = pool.superadmin.eq(&accts.admin.key());
+
+        require_role(pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(accts.pay_mint.key() != SOL_PAY_MINT, SpinError::IncorrectPayMint);
+
+        // like the SOL fee, only the superadmin may change a token's fee rate
+        let pay_token = accts.pay_token_list.get_or_add_pay_token(accts.pay_mint.key(), pool.sol_fee_bps)?;
+        pay_token.decimals = accts.pay_mint.decimals;
+        if is_superadmin && fee_bps != pay_token.fee_bps {
+            require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+            require!(fee_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+            pay_token.fee_bps = fee_bps;
         }
-        pool.sol_price = sol_price;
-        pool.dust_price = dust_price;
-        pool.forge_price = forge_price;
 
-        if pool.superadmin.eq(&accts.admin.key()) {
-            pool.dev_fee = dev_fee;
-            pool.dev_wallet = dev_wallet;
+        // the price and whether it's accepted change what a spin costs, so they follow the timelock;
+        // a new token waits in the live list disabled and unpriced until then
+        if pool.config_delay > 0 {
+            let mint = pay_token.mint;
+            return stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, pool, &accts.pay_token_list, |pending| {
+                pending.set_pay_token(&mint, price, is_enabled)
+            });
+        }
+        pay_token.price = price;
+        pay_token.is_enabled = is_enabled;
+        accts.state.load()?.validate(pool, &accts.pay_token_list)?;
+
+        Ok(())
+    }
+
+    pub fn set_wheel_limits(
+        ctx: Context<SetWheelLimits>,
+        _wheel_id: u64,
+        ratio_total: u64,
+        max_rtp_bps: u64,
+    ) -> Result<()> {
+        msg!("set_wheel_limits");
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        if accts.pool.config_delay > 0 {
+            return stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                pending.ratio_total = ratio_total;
+                pending.max_rtp_bps = max_rtp_bps;
+                Ok(())
+            });
         }
+        accts.pool.ratio_total = ratio_total;
+        accts.pool.max_rtp_bps = max_rtp_bps;
+        accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
 
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn add_item(
         ctx: Context<SpinWheel>,
+        _wheel_id: u64,
         item_mint_list: [Pubkey; 10],
         count: u8,
+        mint_amount_list: [u64; 10],
         token_type: u8,
         ratio: u32,
         amount: u64,
     ) -> Result<()> {
         msg!("add_item");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(accts.pool.config_delay == 0, SpinError::ConfigTimelocked);
 
         let mut state = ctx.accounts.state.load_mut()?;
-        state.add_spinitem(ItemRewardMints{item_mint_list, count}, token_type, ratio, amount)?;
+        state.add_spinitem(ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+        state.validate(&ctx.accounts.pool, &ctx.accounts.pay_token_list)?;
 
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn set_item(
         ctx: Context<SpinWheel>,
+        _wheel_id: u64,
         index: u8,
         item_mint_list: [Pubkey; 10],
         count: u8,
+        mint_amount_list: [u64; 10],
         token_type: u8,
         ratio: u32,
         amount: u64,
         item_count: u8,
     ) -> Result<()> {
         msg!("set_item");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(accts.pool.config_delay == 0, SpinError::ConfigTimelocked);
 
         let mut state = ctx.accounts.state.load_mut()?;
-        state.set_spinitem(index, ItemRewardMints{item_mint_list, count}, token_type, ratio, amount)?;
-        state.count = item_count;
+        state.set_spinitem(index, ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+        state.set_count(item_count)?;
+        state.validate(&ctx.accounts.pool, &ctx.accounts.pay_token_list)?;
 
         Ok(())
     }
 
-    pub fn spin_wheel(ctx: Context<PlayGame>, rand: u32, _round_id: u64, pay_mode: u8,) -> Result<()> {
+    pub fn request_spin_sol<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameSol<'info>>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool,) -> Result<()> {
         let accts = ctx.accounts;
-        let mut pay_amount = 0;
+        accts.pool.require_unpaused(PAUSE_SOL_SPIN)?;
+
+        let pay_amount = accts.pool.sol_price;
+        let vault_amount = accts.pay(ctx.remaining_accounts, pay_amount)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            SOL_PAY_MINT,
+            pay_amount,
+            use_oracle,
+            1,
+        )?;
+        accts.spin_request.vault_amount = vault_amount;
 
-        // pay
-        if pay_mode == 0 {
-            // sol
-            pay_amount = accts.pool.sol_price;
-            let sol_fee = dev_fee(&accts.pool, accts.pool.sol_price)?;
-            let real_sol = accts.pool.sol_price.checked_sub(sol_fee).unwrap();
+        Ok(())
+    }
 
-            invoke(
-                &system_instruction::transfer(&accts.user.key(), &accts.dev_account.key(), sol_fee),
-                &[
-                    accts.user.to_account_info().clone(),
-                    accts.dev_account.clone(),
-                    accts.system_program.to_account_info().clone(),
-                ],
-            )?;
+    pub fn request_spin_token<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameToken<'info>>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool,) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_TOKEN_SPIN)?;
+
+        let pay_token = accts.pay_token_list.get_pay_token(&accts.pay_mint.key())?;
+        let vault_amount = accts.pay(ctx.remaining_accounts, &pay_token, pay_token.price)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            pay_token.mint,
+            pay_token.price,
+            use_oracle,
+            1,
+        )?;
+        accts.spin_request.vault_amount = vault_amount;
 
-            invoke(
-                &system_instruction::transfer(&accts.user.key(), &accts.vault.key(), real_sol),
-                &[
-                    accts.user.to_account_info().clone(),
-                    accts.vault.clone(),
-                    accts.system_program.to_account_info().clone(),
-                ],
-            )?;
-        } else if pay_mode == 1 {
-            // dust
-            require!(
-                accts.pool.dust_mint.eq(&accts.dust_mint.key()),
-                SpinError::IncorreectDustMintKey
-            );
-            let cpi_ctx = CpiContext::new(
-                accts.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: accts.source_account.to_account_info(),
-                    to: accts.dust_vault.to_account_info(),
-                    authority: accts.user.to_account_info(),
-                },
-            );
-            if accts.dust_mint.decimals > REWARD_TOKEN_DECIMAL {
-                let sub_decimals = accts.dust_mint.decimals - REWARD_TOKEN_DECIMAL;
-                let amount = accts.pool.dust_price * ((10 as u64).pow(sub_decimals as u32));
-                anchor_spl::token::transfer(cpi_ctx, amount)?;
-            } else {
-                let sub_decimals = REWARD_TOKEN_DECIMAL - accts.dust_mint.decimals;
-                let amount = accts.pool.dust_price / ((10 as u64).pow(sub_decimals as u32));
-                anchor_spl::token::transfer(cpi_ctx, amount)?;
-            }
-            pay_amount = accts.pool.dust_price;
-        } else  {
-            // forge
-            require!(
-                accts.pool.forge_mint.eq(&accts.forge_mint.key()),
-                SpinError::IncorreectForgeMintKey
-            );
-            let cpi_ctx = CpiContext::new(
-                accts.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: accts.source_account.to_account_info(),
-                    to: accts.forge_vault.to_account_info(),
-                    authority: accts.user.to_account_info(),
-                },
-            );
+        Ok(())
+    }
 
-            if accts.dust_mint.decimals > REWARD_TOKEN_DECIMAL {
-                let sub_decimals = accts.forge_mint.decimals - REWARD_TOKEN_DECIMAL;
-                let amount = accts.pool.forge_price * ((10 as u64).pow(sub_decimals as u32));
-                anchor_spl::token::transfer(cpi_ctx, amount)?;
-            } else {
-                let sub_decimals = REWARD_TOKEN_DECIMAL - accts.forge_mint.decimals;
-                let amount = accts.pool.forge_price / ((10 as u64).pow(sub_decimals as u32));
-                anchor_spl::token::transfer(cpi_ctx, amount)?;
-            }
-            pay_amount = accts.pool.forge_price;
+    // spin_count results in one request, charged through the pool's bulk discount tiers
+    pub fn spin_many_sol<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameSol<'info>>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool, spin_count: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_SOL_SPIN)?;
+
+        let pay_amount = accts.pool.bulk_price(accts.pool.sol_price, spin_count)?;
+        let vault_amount = accts.pay(ctx.remaining_accounts, pay_amount)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            SOL_PAY_MINT,
+            pay_amount,
+            use_oracle,
+            spin_count,
+        )?;
+        accts.spin_request.vault_amount = vault_amount;
+
+        Ok(())
+    }
+
+    pub fn spin_many_token<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameToken<'info>>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool, spin_count: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_TOKEN_SPIN)?;
+
+        let pay_token = accts.pay_token_list.get_pay_token(&accts.pay_mint.key())?;
+        let pay_amount = accts.pool.bulk_price(pay_token.price, spin_count)?;
+        let vault_amount = accts.pay(ctx.remaining_accounts, &pay_token, pay_amount)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            pay_token.mint,
+            pay_amount,
+            use_oracle,
+            spin_count,
+        )?;
+        accts.spin_request.vault_amount = vault_amount;
+
+        Ok(())
+    }
+
+    pub fn set_bulk_discounts(ctx: Context<SetBulkDiscounts>, _wheel_id: u64, min_count_list: Vec<u8>, discount_bps_list: Vec<u64>) -> Result<()> {
+        msg!("set_bulk_discounts");
+        let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        // discounts lower the effective price, so they follow the timelock
+        if accts.pool.config_delay > 0 {
+            return stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                pending.set_bulk_discounts(&min_count_list, &discount_bps_list)
+            });
         }
+        accts.pool.set_bulk_discounts(&min_count_list, &discount_bps_list)?;
+        accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_credit_sol(ctx: Context<ManageCreditSol>, _wheel_id: u64, amount: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_SOL_SPIN)?;
+
+        invoke(
+            &system_instruction::transfer(&accts.user.key(), &accts.vault.key(), amount),
+            &[
+                accts.user.to_account_info().clone(),
+                accts.vault.clone(),
+                accts.system_program.to_account_info().clone(),
+            ],
+        )?;
 
         if accts.user_state.is_initialized == 0 {
             accts.user_state.is_initialized = 1;
             accts.user_state.user = accts.user.key();
-            accts.user_state.round_num = 1;
-        } else {
-            require!(
-                accts.user_state.user.eq(&accts.user.key()),
-                SpinError::IncorrectUserState
-            );
-            accts.user_state.round_num = accts.user_state.round_num + 1;
         }
+        accts.user_state.add_credit(&accts.pool.key(), &SOL_PAY_MINT, amount)?;
+        accts.liabilities.load_mut()?.add_sol(amount)?;
 
-        // generate random winner
-        let pyth_price_info = &accts.pyth_account;
-        let pyth_price_data = &pyth_price_info.try_borrow_data()?;
-        let pyth_price = pyth_client::cast::<pyth_client::Price>(pyth_price_data);
-        let agg_price = pyth_price.agg.price as u64;
-        let rand_spin = agg_price + rand as u64;
+        Ok(())
+    }
 
-        let mut state = accts.state.load_mut()?;
-        state.get_spinresult(rand_spin as u32);
+    pub fn withdraw_credit_sol(ctx: Context<ManageCreditSol>, wheel_id: u64, amount: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_CLAIM)?;
 
-        let amount = state.amount_list[state.last_spinindex as usize];
-        let reward_mints = state.reward_mint_list[state.last_spinindex as usize];
-        for i in 0..reward_mints.count {
-            accts.user_pendingstate.add_item(reward_mints.item_mint_list[i as usize], amount)?;
-        }
+        accts.user_state.spend_credit(&accts.pool.key(), &SOL_PAY_MINT, amount)?;
+        accts.liabilities.load_mut()?.sub_sol(amount);
 
-        accts.user_pendingstate.user = accts.user.key();
-        accts.user_pendingstate.is_claimed = 0;
-        accts.user_pendingstate.round_num = accts.user_state.round_num;
-        accts.user_pendingstate.is_sol = state.token_type_list[state.last_spinindex as usize] == 2;
-        if accts.user_pendingstate.is_sol {
-            accts.user_pendingstate.sol_amount = amount;
-        }
+        transfer_sol_from_vault(
+            &accts.vault,
+            &accts.user.to_account_info(),
+            &accts.system_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("vault").unwrap(),
+            amount,
+        )
+    }
 
-        let mut one_rmint = Pubkey::default();
-        if reward_mints.count > 0 {
-            one_rmint = reward_mints.item_mint_list[0];
-        }
+    pub fn deposit_credit_token(ctx: Context<ManageCreditToken>, _wheel_id: u64, amount: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_TOKEN_SPIN)?;
+        let pay_token = accts.pay_token_list.get_pay_token(&accts.pay_mint.key())?;
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: accts.user_token_account.to_account_info(),
+                to: accts.pay_vault.to_account_info(),
+                authority: accts.user.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer(cpi_ctx, amount)?;
 
-        accts.last_users.push_front_last_user(accts.user.key(), pay_amount, amount, one_rmint, state.token_type_list[state.last_spinindex as usize]);
+        if accts.user_state.is_initialized == 0 {
+            accts.user_state.is_initialized = 1;
+            accts.user_state.user = accts.user.key();
+        }
+        accts.user_state.add_credit(&accts.pool.key(), &pay_token.mint, amount)?;
+        accts.liabilities.load_mut()?.add(&pay_token.mint, amount)?;
 
         Ok(())
     }
 
-    pub fn claim(
-        ctx : Context<Claim>,
-        amount: u64,
-        is_sol: bool,
-        ) -> Result<()> {
-        let user_pendingstate = &mut ctx.accounts.user_pendingstate;
+    pub fn withdraw_credit_token(ctx: Context<ManageCreditToken>, wheel_id: u64, amount: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_CLAIM)?;
+
+        accts.user_state.spend_credit(&accts.pool.key(), &accts.pay_mint.key(), amount)?;
+        accts.liabilities.load_mut()?.sub(&accts.pay_mint.key(), amount);
+
+        transfer_token_from_pool(
+            &accts.pool.to_account_info(),
+            &accts.pay_vault.to_account_info(),
+            &accts.user_token_account.to_account_info(),
+            &accts.token_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("pool").unwrap(),
+            amount,
+        )
+    }
 
-        if is_sol == true {
-            require!(user_pendingstate.is_sol && user_pendingstate.is_claimed == 0 && amount == user_pendingstate.sol_amount, SpinError::InvalidReward);
+    // UserState only grows by appending fields, so older accounts are extended with
+    // zeroed bytes; they don't deserialize until this ran once.
+    pub fn migrate_user_state(ctx: Context<MigrateUserState>) -> Result<()> {
+        let accts = ctx.accounts;
+        let user_state = accts.user_state.to_account_info();
+        require!(user_state.owner == ctx.program_id, SpinError::IncorrectUserState);
 
-            let bump = ctx.bumps.get("vault").unwrap();
-            invoke_signed(
-                &system_instruction::transfer(&ctx.accounts.vault.key(), &ctx.accounts.owner.key(), amount),
+        let new_len = 8 + size_of::<UserState>();
+        if user_state.data_len() >= new_len {
+            return Ok(());
+        }
+
+        let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(user_state.lamports());
+        if shortfall > 0 {
+            invoke(
+                &system_instruction::transfer(&accts.user.key(), &user_state.key(), shortfall),
                 &[
-                    ctx.accounts.vault.to_account_info().clone(),
-                    ctx.accounts.owner.clone(),
-                    ctx.accounts.system_program.to_account_info().clone(),
+                    accts.user.to_account_info(),
+                    user_state.clone(),
+                    accts.system_program.to_account_info(),
                 ],
-                &[&[VAULT_SEED, &[*bump]]],
-            )?;
-        } else {
-            let reward_mint = ctx.accounts.source_reward_account.mint;
-            let mut is_found = false;
-            let mut found_idx = 0;
-
-            for i in 0..user_pendingstate.count {
-                if user_pendingstate.pending_mint_list[i as usize].eq(&reward_mint) && user_pendingstate.is_claimed_list[i as usize] == false {
-                    is_found = true;
-                    found_idx = i;
-                    break;
-                }
-            }
-
-            require!(is_found, SpinError::InvalidReward);
-
-            let (_vault_authority, vault_authority_bump) =
-            Pubkey::find_program_address(&[ESCROW_PDA_SEED.as_ref()], ctx.program_id);
-            let authority_seeds = &[&ESCROW_PDA_SEED.as_bytes()[..], &[vault_authority_bump]];
-
-            token::transfer(
-                ctx.accounts.into_transfer_to_pda_context()
-                    .with_signer(&[&authority_seeds[..]]),
-            amount,
             )?;
         }
+        user_state.realloc(new_len, true)?;
 
         Ok(())
     }
 
-    pub fn withdraw_paid_tokens(
-        ctx : Context<Withdraw>,
-        amount: u64,
-        ) -> Result<()> {
+    // pays for spin_count spins out of the user's SOL credits
+    pub fn spin_with_credit_sol<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameCreditSol<'info>>, wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool, spin_count: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_SOL_SPIN)?;
+
+        let pay_amount = accts.pool.bulk_price(accts.pool.sol_price, spin_count)?;
+        let vault_amount = accts.spend(ctx.remaining_accounts, wheel_id, *ctx.bumps.get("vault").unwrap(), pay_amount)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            SOL_PAY_MINT,
+            pay_amount,
+            use_oracle,
+            spin_count,
+        )?;
+        accts.spin_request.vault_amount = vault_amount;
 
-        let (_vault_authority, vault_authority_bump) =
-        Pubkey::find_program_address(&[ESCROW_PDA_SEED.as_ref()], ctx.program_id);
-        let authority_seeds = &[&ESCROW_PDA_SEED.as_bytes()[..], &[vault_authority_bump]];
+        Ok(())
+    }
 
-        token::transfer(
-            ctx.accounts.into_transfer_from_pda_context()
-                .with_signer(&[&authority_seeds[..]]),
-        amount,
+    pub fn spin_with_credit_token<'info>(ctx: Context<'_, '_, '_, 'info, PlayGameCreditToken<'info>>, wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool, spin_count: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_TOKEN_SPIN)?;
+
+        let pay_token = accts.pay_token_list.get_pay_token(&accts.pay_mint.key())?;
+        let pay_amount = accts.pool.bulk_price(pay_token.price, spin_count)?;
+        let vault_amount = accts.spend(ctx.remaining_accounts, wheel_id, *ctx.bumps.get("pool").unwrap(), &pay_token, pay_amount)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            pay_token.mint,
+            pay_amount,
+            use_oracle,
+            spin_count,
         )?;
+        accts.spin_request.vault_amount = vault_amount;
+
+        Ok(())
+    }
 
+    pub fn init_ticket_mint(_ctx: Context<InitTicketMint>, _wheel_id: u64) -> Result<()> {
+        msg!("init_ticket_mint");
         Ok(())
     }
 
-    pub fn withdraw_sol( ctx : Context<WithdrawSol>, amount: u64) -> Result<()> {
+    // giveaways and airdrops, every ticket is redeemable for one spin
+    pub fn mint_tickets(ctx: Context<MintTickets>, wheel_id: u64, amount: u64) -> Result<()> {
         let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_PROMOTER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        let wheel_id_bytes = wheel_id.to_le_bytes();
+        let authority_seeds = &[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes, &[*ctx.bumps.get("pool").unwrap()]];
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            MintTo {
+                mint: accts.ticket_mint.to_account_info(),
+                to: accts.dest_account.to_account_info(),
+                authority: accts.pool.to_account_info(),
+            },
+        );
+        token::mint_to(cpi_ctx.with_signer(&[&authority_seeds[..]]), amount)
+    }
 
-        // send fee to treasury
-        let bump = ctx.bumps.get("vault").unwrap();
-        invoke_signed(
-            &system_instruction::transfer(&accts.vault.key(), &accts.dest_account.key(), amount),
-            &[
-                accts.vault.to_account_info().clone(),
-                accts.dest_account.clone(),
-                accts.system_program.to_account_info().clone(),
-            ],
-            &[&[VAULT_SEED, &[*bump]]],
+    // burns spin_count tickets, nothing is charged and no revenue is split
+    pub fn request_spin_ticket(ctx: Context<PlayGameTicket>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool, spin_count: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_TICKET_SPIN)?;
+        require!((1..=MAX_SPIN_COUNT).contains(&spin_count), SpinError::InvalidSpinCount);
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            Burn {
+                mint: accts.ticket_mint.to_account_info(),
+                from: accts.ticket_account.to_account_info(),
+                authority: accts.user.to_account_info(),
+            },
+        );
+        token::burn(cpi_ctx, spin_count as u64)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            accts.ticket_mint.key(),
+            0,
+            use_oracle,
+            spin_count,
         )?;
+        accts.spin_request.is_ticket = true;
 
         Ok(())
     }
 
-    pub fn close_user_pending_acc(ctx : Context<CloseUserPendingAcc>) -> Result<()> {
-        ctx.accounts.user_pendingstate.is_claimed = 1;
-
+    pub fn init_free_spin_items(ctx: Context<InitFreeSpinItems>, _wheel_id: u64) -> Result<()> {
+        msg!("init_free_spin_items");
+        let mut _free_state = ctx.accounts.free_state.load_init()?;
         Ok(())
     }
 
-    pub fn add_admin(ctx : Context<ManageAdmin>) -> Result<()> {
-        ctx.accounts.admin_info.add_admin(ctx.accounts.admin.key())?;
+    // free spins cost nothing, so their odds aren't timelocked
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_free_spin_item(
+        ctx: Context<SetFreeSpinItem>,
+        _wheel_id: u64,
+        index: u8,
+        item_mint_list: [Pubkey; 10],
+        count: u8,
+        mint_amount_list: [u64; 10],
+        token_type: u8,
+        ratio: u32,
+        amount: u64,
+        item_count: u8,
+    ) -> Result<()> {
+        msg!("set_free_spin_item");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        let mut free_state = ctx.accounts.free_state.load_mut()?;
+        free_state.set_spinitem(index, ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+        free_state.set_count(item_count)?;
+        free_state.validate_items(&ctx.accounts.pool)?;
+
         Ok(())
     }
 
-    pub fn delete_admin(ctx : Context<ManageAdmin>) -> Result<()> {
-        ctx.accounts.admin_info.delete_admin(ctx.accounts.admin.key())?;
+    pub fn set_free_spin_period(ctx: Context<SetFreeSpinPeriod>, _wheel_id: u64, free_spin_period: i64) -> Result<()> {
+        let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(free_spin_period >= 0, SpinError::InvalidFreeSpinPeriod);
+
+        accts.pool.free_spin_period = free_spin_period;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut, signer)]
-    pub initializer: AccountInfo<'info>,
+    pub fn request_free_spin(ctx: Context<PlayGameFree>, _wheel_id: u64, commitment: [u8; 32], _round_id: u64, use_oracle: bool) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_FREE_SPIN)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let pool = accts.pool.key();
+        accts.user_state.take_free_spin(&pool, now, accts.pool.free_spin_period)?;
+
+        open_spin_request(
+            &mut accts.user_state,
+            &mut accts.spin_request,
+            accts.user.key(),
+            accts.pool.key(),
+            commitment,
+            Pubkey::default(),
+            0,
+            use_oracle,
+            1,
+        )?;
+        accts.spin_request.is_free = true;
 
-    #[account(init, seeds=[ESCROW_PDA_SEED.as_ref()], bump, payer=initializer, space=size_of::<Pool>() + 8)]
-    pub pool : Box<Account<'info, Pool>>,
+        Ok(())
+    }
 
-    #[account(init, seeds=[LAST_USERS_SEED.as_ref()], bump, payer=initializer, space=size_of::<LatestUsers>() + 8)]
-    pub last_users : Box<Account<'info, LatestUsers>>,
+    // remaining accounts: the pool's reward vault for every reward mint on the wheel,
+    // plus the jackpot vault's ATA for every mint of a jackpot item
+    pub fn settle_spin<'info>(ctx: Context<'_, '_, '_, 'info, SettleSpin<'info>>, wheel_id: u64, _round_id: u64, seed: [u8; 32]) -> Result<()> {
+        let accts = ctx.accounts;
+        let spin_request = &accts.spin_request;
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub super_admin: AccountInfo<'info>,
+        require!(!spin_request.use_oracle, SpinError::IncorrectRandomnessSource);
+        require!(
+            hash::hashv(&[&seed]).to_bytes() == spin_request.commitment,
+            SpinError::InvalidSpinCommitment
+        );
 
-    #[account(zero)]
-    pub state : AccountLoader<'info, SpinItemList>,
+        // generate random winner
+        let slot_hash = get_slot_hash(&accts.slot_hashes, spin_request.target_slot)?;
+        let rand_spin = hash::hashv(&[&seed, &slot_hash, spin_request.key().as_ref()]).to_bytes();
 
-    #[account(init, seeds=[ADMIN_LIST_SEED], bump, payer=initializer, space=size_of::<AdminInfo>() + 8)]
-    pub admin_info : Box<Account<'info, AdminInfo>>,
+        let mut state = accts.state.load_mut()?;
+        let jackpot_payouts = record_spin_result(
+            &accts.pool,
+            &mut state,
+            &mut *accts.liabilities.load_mut()?,
+            &mut accts.jackpot,
+            &mut accts.user_pendingstate,
+            &mut accts.last_users,
+            spin_request,
+            &accts.vault,
+            &accts.jackpot_vault,
+            ctx.remaining_accounts,
+            rand_spin,
+        )?;
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub system_program: Program<'info, System>,
-}
+        for payout in jackpot_payouts.iter() {
+            pay_jackpot(
+                payout,
+                &accts.pool.key(),
+                wheel_id,
+                *ctx.bumps.get("jackpot_vault").unwrap(),
+                &accts.jackpot_vault,
+                &accts.vault,
+                ctx.remaining_accounts,
+                &accts.system_program.to_account_info(),
+                &accts.token_program.to_account_info(),
+            )?;
+        }
 
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct SetPayInfo<'info> {
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    pub fn request_randomness(ctx: Context<RequestRandomness>, _wheel_id: u64, _round_id: u64) -> Result<()> {
+        let randomness_request = &mut ctx.accounts.randomness_request;
+        randomness_request.spin_request = ctx.accounts.spin_request.key();
+        randomness_request.pool = ctx.accounts.spin_request.pool;
+        randomness_request.requester = ctx.accounts.user.key();
 
-    #[account(
-        mut,
-        seeds=[ESCROW_PDA_SEED.as_ref()],
-        bump,
-    )]
-    pub pool : Box<Account<'info, Pool>>,
+        let clock = Clock::get()?;
+        randomness_request.seed = hash::hashv(&[
+            ctx.accounts.spin_request.key().as_ref(),
+            &clock.slot.to_le_bytes(),
+            &clock.unix_timestamp.to_le_bytes(),
+        ]).to_bytes();
 
-    #[account(mut)]
-    pub admin_info : Account<'info, AdminInfo>,
+        Ok(())
+    }
 
-    // dust mint
-    pub dust_mint: Account<'info, Mint>,
+    pub fn fulfill_randomness(ctx: Context<FulfillRandomness>, signature: [u8; 64]) -> Result<()> {
+        let accts = ctx.accounts;
 
-    // forge mint
-    pub forge_mint: Account<'info, Mint>,
-}
+        require!(accts.pool.is_oracle(&accts.oracle.key()), SpinError::IncorrectOracle);
 
-#[derive(Accounts)]
-pub struct SpinWheel<'info> {
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut)]
-    pub superadmin: Signer<'info>,
+        // the oracle signs (request, seed) in an ed25519 instruction right before this one.
+        // This only proves which oracle fulfilled the request: a signer can produce many
+        // valid signatures of one message and pick the outcome it likes, so registered
+        // oracles are fully trusted with the result.
+        let current_index = load_current_index_checked(&accts.instructions)?;
+        require!(current_index > 0, SpinError::InvalidOracleProof);
+        let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, &accts.instructions)?;
 
-    #[account(
-        mut,
-        seeds=[ESCROW_PDA_SEED.as_ref()],
-        bump,
-        constraint = pool.superadmin == superadmin.key(),
-    )]
-    pub pool : Box<Account<'info, Pool>>,
+        let message = [accts.randomness_request.key().to_bytes(), accts.randomness_request.seed].concat();
+        verify_ed25519_ix(&ed25519_ix, &accts.oracle.key(), &message, &signature)?;
 
-    #[account(mut)]
-    pub state : AccountLoader<'info, SpinItemList>,
-}
+        let randomness_request = &mut accts.randomness_request;
+        randomness_request.randomness = hash::hashv(&[&signature]).to_bytes();
+        randomness_request.oracle = accts.oracle.key();
+        randomness_request.is_fulfilled = true;
 
-#[derive(Accounts)]
-#[instruction(rand: u32, round_id : u64)]
-pub struct PlayGame<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(mut, seeds=[ESCROW_PDA_SEED.as_ref()], bump)]
-    pub pool : Box<Account<'info, Pool>>,
+    // remaining accounts: same reward vaults as settle_spin
+    pub fn settle_oracle_spin<'info>(ctx: Context<'_, '_, '_, 'info, SettleOracleSpin<'info>>, wheel_id: u64, _round_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
 
-    #[account(mut)]
-    pub last_users : Box<Account<'info, LatestUsers>>,
+        let randomness = accts.randomness_request.randomness;
+        let mut state = accts.state.load_mut()?;
+        let jackpot_payouts = record_spin_result(
+            &accts.pool,
+            &mut state,
+            &mut *accts.liabilities.load_mut()?,
+            &mut accts.jackpot,
+            &mut accts.user_pendingstate,
+            &mut accts.last_users,
+            &accts.spin_request,
+            &accts.vault,
+            &accts.jackpot_vault,
+            ctx.remaining_accounts,
+            randomness,
+        )?;
 
-    #[account(mut)]
-    pub state : AccountLoader<'info, SpinItemList>,
+        for payout in jackpot_payouts.iter() {
+            pay_jackpot(
+                payout,
+                &accts.pool.key(),
+                wheel_id,
+                *ctx.bumps.get("jackpot_vault").unwrap(),
+                &accts.jackpot_vault,
+                &accts.vault,
+                ctx.remaining_accounts,
+                &accts.system_program.to_account_info(),
+                &accts.token_program.to_account_info(),
+            )?;
+        }
 
-    #[account(
-        init_if_needed,
-        seeds = [USER_STATE_SEED, user.key().as_ref()],
-        bump,
-        payer = user,
-        space = 8 + size_of::<UserState>()
-    )]
-    pub user_state: Box<Account<'info, UserState>>,
+        Ok(())
+    }
+
+    pub fn add_oracle(ctx : Context<ManageOracle>, _wheel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        ctx.accounts.pool.add_oracle(ctx.accounts.oracle.key())?;
+        Ok(())
+    }
+
+    pub fn delete_oracle(ctx : Context<ManageOracle>, _wheel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        ctx.accounts.pool.delete_oracle(ctx.accounts.oracle.key())?;
+        Ok(())
+    }
+
+    pub fn claim(
+        ctx : Context<Claim>,
+        wheel_id: u64,
+        is_sol: bool,
+        ) -> Result<()> {
+        ctx.accounts.pool.require_unpaused(PAUSE_CLAIM)?;
+        let user_pendingstate = &mut ctx.accounts.user_pendingstate;
+
+        if is_sol {
+            let amount = user_pendingstate.take_sol_reward()?;
+            ctx.accounts.liabilities.load_mut()?.sub_sol(amount);
+
+            let bump = ctx.bumps.get("vault").unwrap();
+            invoke_signed(
+                &system_instruction::transfer(&ctx.accounts.vault.key(), &ctx.accounts.owner.key(), amount),
+                &[
+                    ctx.accounts.vault.to_account_info().clone(),
+                    ctx.accounts.owner.clone(),
+                    ctx.accounts.system_program.to_account_info().clone(),
+                ],
+                &[&[VAULT_SEED, &wheel_id.to_le_bytes(), &[*bump]]],
+            )?;
+        } else {
+            let reward_mint = ctx.accounts.source_reward_account.mint;
+            check_reward_accounts(
+                &ctx.accounts.source_reward_account,
+                &ctx.accounts.dest_reward_account,
+                &ctx.accounts.pool.key(),
+                &ctx.accounts.owner.key(),
+                &reward_mint,
+            )?;
+            let amount = user_pendingstate.take_token_reward(&reward_mint)?;
+            ctx.accounts.liabilities.load_mut()?.sub(&reward_mint, amount);
+
+            let wheel_id_bytes = wheel_id.to_le_bytes();
+            let (_vault_authority, vault_authority_bump) =
+            Pubkey::find_program_address(&[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes], ctx.program_id);
+            let authority_seeds = &[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes, &[vault_authority_bump]];
+
+            token::transfer(
+                ctx.accounts.transfer_to_pda_context()
+                    .with_signer(&[&authority_seeds[..]]),
+            amount,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn claim_all<'info>(ctx: Context<'_, '_, '_, 'info, ClaimAll<'info>>, wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.require_unpaused(PAUSE_CLAIM)?;
+        let user_pendingstate = &mut accts.user_pendingstate;
+        let mut liabilities = accts.liabilities.load_mut()?;
+
+        if user_pendingstate.is_sol && user_pendingstate.is_claimed == 0 {
+            let amount = user_pendingstate.take_sol_reward()?;
+            liabilities.sub_sol(amount);
+
+            let bump = ctx.bumps.get("vault").unwrap();
+            invoke_signed(
+                &system_instruction::transfer(&accts.vault.key(), &accts.owner.key(), amount),
+                &[
+                    accts.vault.to_account_info().clone(),
+                    accts.owner.clone(),
+                    accts.system_program.to_account_info().clone(),
+                ],
+                &[&[VAULT_SEED, &wheel_id.to_le_bytes(), &[*bump]]],
+            )?;
+        }
+
+        // one (source vault, owner token account) pair per unclaimed mint, in pending order
+        let wheel_id_bytes = wheel_id.to_le_bytes();
+        let bump = ctx.bumps.get("pool").unwrap();
+        let authority_seeds = &[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes, &[*bump]];
+
+        let mut pairs = ctx.remaining_accounts.chunks(2);
+        for i in 0..user_pendingstate.count as usize {
+            if user_pendingstate.is_claimed_list[i] {
+                continue;
+            }
+            let pair = pairs.next().ok_or(SpinError::InvalidReward)?;
+            require!(pair.len() == 2, SpinError::InvalidReward);
+
+            let reward_mint = user_pendingstate.pending_mint_list[i];
+            let source_reward_account = Account::<TokenAccount>::try_from(&pair[0])?;
+            let dest_reward_account = Account::<TokenAccount>::try_from(&pair[1])?;
+            check_reward_accounts(
+                &source_reward_account,
+                &dest_reward_account,
+                &accts.pool.key(),
+                &accts.owner.key(),
+                &reward_mint,
+            )?;
+            let amount = user_pendingstate.take_token_reward(&reward_mint)?;
+            liabilities.sub(&reward_mint, amount);
+
+            let cpi_ctx = CpiContext::new(
+                accts.token_program.to_account_info(),
+                Transfer {
+                    from: pair[0].clone(),
+                    to: pair[1].clone(),
+                    authority: accts.pool.to_account_info(),
+                },
+            );
+            token::transfer(
+                cpi_ctx.with_signer(&[&authority_seeds[..]]),
+                amount,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn withdraw_paid_tokens(
+        ctx : Context<Withdraw>,
+        wheel_id: u64,
+        amount: u64,
+        ) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        accts.pool.require_unpaused(PAUSE_WITHDRAW)?;
+        authorize_withdrawal(
+            &accts.pool,
+            &accts.admin_info,
+            &mut accts.treasury,
+            &accts.authority.key(),
+            &accts.dest_account.owner,
+            &accts.source_account.mint,
+            amount,
+        )?;
+        require_token_reserves(&accts.source_account, &*accts.liabilities.load()?, amount)?;
+
+        transfer_token_from_pool(
+            &accts.pool.to_account_info(),
+            &accts.source_account.to_account_info(),
+            &accts.dest_account.to_account_info(),
+            &accts.token_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("pool").unwrap(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_sol( ctx : Context<WithdrawSol>, wheel_id: u64, amount: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        accts.pool.require_unpaused(PAUSE_WITHDRAW)?;
+        authorize_withdrawal(
+            &accts.pool,
+            &accts.admin_info,
+            &mut accts.treasury,
+            &accts.authority.key(),
+            &accts.dest_account.key(),
+            &SOL_PAY_MINT,
+            amount,
+        )?;
+        require_sol_reserves(&accts.vault, &*accts.liabilities.load()?, amount)?;
+
+        transfer_sol_from_vault(
+            &accts.vault,
+            &accts.dest_account,
+            &accts.system_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("vault").unwrap(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_treasury_destination(ctx: Context<ManageTreasury>, _wheel_id: u64, destination: Pubkey, is_allowed: bool) -> Result<()> {
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        ctx.accounts.treasury.set_destination(destination, is_allowed)?;
+        Ok(())
+    }
+
+    pub fn set_treasury_cap(ctx: Context<ManageTreasury>, _wheel_id: u64, mint: Pubkey, epoch_cap: u64) -> Result<()> {
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        ctx.accounts.treasury.set_cap(mint, epoch_cap)?;
+        Ok(())
+    }
+
+    // Closes a spin request that can't be settled anymore. A commit-reveal spin
+    // expires only after its result was already computable by the player, so its
+    // payment stays with the pool; an oracle spin that never got randomness gets
+    // what its payment left in the vault back as spin credits, the revenue split
+    // and jackpot share are already paid out.
+    pub fn close_expired_spin(ctx: Context<CloseExpiredSpin>, _wheel_id: u64, _round_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        let spin_request = &accts.spin_request;
+        require!(
+            Clock::get()?.slot > spin_request.target_slot.checked_add(SLOT_HASHES_MAX_AGE).unwrap(),
+            SpinError::SpinNotExpired
+        );
+
+        if !spin_request.use_oracle {
+            return Ok(());
+        }
+
+        let randomness_info = accts.randomness_request.to_account_info();
+        if !randomness_info.data_is_empty() {
+            let randomness_request = Account::<RandomnessRequest>::try_from(&randomness_info)?;
+            require!(!randomness_request.is_fulfilled, SpinError::RandomnessAlreadyFulfilled);
+            randomness_request.close(accts.user.to_account_info())?;
+        }
+
+        // ticket and free spins leave nothing in the vault
+        let refund = spin_request.vault_amount;
+        if refund > 0 {
+            let pool = accts.pool.key();
+            accts.user_state.add_credit(&pool, &spin_request.pay_mint, refund)?;
+            let mut liabilities = accts.liabilities.load_mut()?;
+            if spin_request.pay_mint.eq(&SOL_PAY_MINT) {
+                liabilities.add_sol(refund)?;
+            } else {
+                liabilities.add(&spin_request.pay_mint, refund)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn close_user_pending_acc(ctx : Context<CloseUserPendingAcc>, _wheel_id: u64) -> Result<()> {
+        // unclaimed rewards are forfeited
+        ctx.accounts.liabilities.load_mut()?.release(&ctx.accounts.user_pendingstate);
+        ctx.accounts.user_pendingstate.is_claimed = 1;
+
+        Ok(())
+    }
+
+    pub fn add_admin(ctx : Context<ManageAdmin>, _wheel_id: u64, roles: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        authorize_admin_change(&accts.pool, &accts.admin_info, &accts.authority.key(), &accts.admin.key(), roles)?;
+        accts.admin_info.add_admin(accts.admin.key(), roles)?;
+        Ok(())
+    }
+
+    pub fn delete_admin(ctx : Context<ManageAdmin>, _wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        authorize_admin_change(&accts.pool, &accts.admin_info, &accts.authority.key(), &accts.admin.key(), 0)?;
+        accts.admin_info.delete_admin(accts.admin.key())?;
+        Ok(())
+    }
+
+    pub fn grant_role(ctx : Context<ManageAdmin>, _wheel_id: u64, roles: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        authorize_admin_change(&accts.pool, &accts.admin_info, &accts.authority.key(), &accts.admin.key(), roles)?;
+        accts.admin_info.grant_role(accts.admin.key(), roles)?;
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx : Context<ManageAdmin>, _wheel_id: u64, roles: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        authorize_admin_change(&accts.pool, &accts.admin_info, &accts.authority.key(), &accts.admin.key(), roles)?;
+        accts.admin_info.revoke_role(accts.admin.key(), roles)?;
+        Ok(())
+    }
+
+    pub fn set_config_delay(ctx: Context<SetConfigDelay>, _wheel_id: u64, config_delay: i64) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        set_config_delay_checked(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &mut accts.pool, &accts.pay_token_list, config_delay)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn stage_item(
+        ctx: Context<StageConfig>,
+        _wheel_id: u64,
+        index: u8,
+        item_mint_list: [Pubkey; 10],
+        count: u8,
+        mint_amount_list: [u64; 10],
+        token_type: u8,
+        ratio: u32,
+        amount: u64,
+        item_count: u8,
+    ) -> Result<()> {
+        msg!("stage_item");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+            pending.items.set_spinitem(index, ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+            pending.items.set_count(item_count)
+        })
+    }
+
+    pub fn stage_pay_info(ctx: Context<StageConfig>, _wheel_id: u64, sol_price: u64) -> Result<()> {
+        msg!("stage_pay_info");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+            pending.sol_price = sol_price;
+            Ok(())
+        })
+    }
+
+    pub fn stage_pay_token_price(ctx: Context<StageConfig>, _wheel_id: u64, mint: Pubkey, price: u64) -> Result<()> {
+        msg!("stage_pay_token_price");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_PRICING_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+            pending.set_token_price(&mint, price)
+        })
+    }
+
+    pub fn cancel_staged_config(ctx: Context<StageConfig>, _wheel_id: u64) -> Result<()> {
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        accts.pending_config.load_mut()?.clear();
+        Ok(())
+    }
+
+    // anyone may activate a staged config once its delay has passed
+    pub fn activate_config(ctx: Context<ActivateConfig>, _wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        let mut pending = accts.pending_config.load_mut()?;
+        require!(pending.is_staged == 1, SpinError::ConfigNotStaged);
+        require!(Clock::get()?.unix_timestamp >= pending.activates_at, SpinError::ConfigNotReady);
+
+        pending.apply(&mut *accts.state.load_mut()?, &mut accts.pool, &mut accts.pay_token_list)
+    }
+
+    // pausing is an emergency switch, so it is not routed through the multisig
+    pub fn set_pause(ctx: Context<SetPause>, _wheel_id: u64, pause_flags: u8) -> Result<()> {
+        let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.authority.key(), ROLE_PAUSER)?;
+        require!(pause_flags & !PAUSE_ALL == 0, SpinError::InvalidPauseFlags);
+
+        accts.pool.pause_flags = pause_flags;
+        Ok(())
+    }
+
+    pub fn set_item_stock(
+        ctx: Context<StageConfig>,
+        _wheel_id: u64,
+        index: u8,
+        has_stock_limit: bool,
+        fallback_index: u8,
+    ) -> Result<()> {
+        msg!("set_item_stock");
+        let accts = &ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.admin.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!((index as usize) < SPIN_ITEM_COUNT, SpinError::IndexOverflowSetItem);
+
+        // the fallback changes the odds once an item sells out, so it follows the timelock
+        if accts.pool.config_delay > 0 {
+            stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                pending.items.has_stock_limit_list[index as usize] = has_stock_limit as u8;
+                pending.items.fallback_index_list[index as usize] = fallback_index;
+                Ok(())
+            })
+        } else {
+            let mut state = accts.state.load_mut()?;
+            state.has_stock_limit_list[index as usize] = has_stock_limit as u8;
+            state.fallback_index_list[index as usize] = fallback_index;
+            state.validate(&accts.pool, &accts.pay_token_list)
+        }
+    }
+
+    pub fn restock_item(ctx: Context<RestockItem>, _wheel_id: u64, index: u8, stock: u32) -> Result<()> {
+        let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.authority.key(), ROLE_ODDS_MANAGER)?;
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(index < accts.state.load()?.count, SpinError::IndexOverflowSetItem);
+
+        // bringing a sold-out item back changes the odds, so it follows the timelock
+        if accts.pool.config_delay > 0 {
+            return stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                pending.restock_list[index as usize] = stock;
+                pending.is_restock_list[index as usize] = 1;
+                Ok(())
+            });
+        }
+        accts.state.load_mut()?.stock_list[index as usize] = stock;
+        Ok(())
+    }
+
+    pub fn set_item_paused(ctx: Context<SetItemPaused>, _wheel_id: u64, index: u8, is_paused: bool) -> Result<()> {
+        let accts = ctx.accounts;
+        require_role(&accts.pool, &accts.admin_info, &accts.authority.key(), ROLE_PAUSER)?;
+
+        let mut state = accts.state.load_mut()?;
+        require!(index < state.count, SpinError::IndexOverflowSetItem);
+        state.item_paused_list[index as usize] = is_paused as u8;
+        Ok(())
+    }
+
+    pub fn propose_superadmin(ctx: Context<TransferSuperadmin>, _wheel_id: u64, new_superadmin: Pubkey) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(accts.pool.superadmin == accts.authority.key(), SpinError::IncorrectSuperAdminOrAdmin);
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+        require!(new_superadmin != Pubkey::default(), SpinError::IncorrectSuperAdminOrAdmin);
+
+        accts.pool.pending_superadmin = new_superadmin;
+        Ok(())
+    }
+
+    pub fn accept_superadmin(ctx: Context<TransferSuperadmin>, _wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.pool.accept_superadmin(&mut accts.admin_info, accts.authority.key())
+    }
+
+    // either side may drop a pending transfer
+    pub fn cancel_superadmin_transfer(ctx: Context<TransferSuperadmin>, _wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        let authority = accts.authority.key();
+        require!(
+            accts.pool.superadmin == authority || accts.pool.pending_superadmin == authority,
+            SpinError::IncorrectSuperAdminOrAdmin
+        );
+
+        accts.pool.pending_superadmin = Pubkey::default();
+        Ok(())
+    }
+
+    pub fn renounce_superadmin(ctx: Context<TransferSuperadmin>, _wheel_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(accts.pool.superadmin == accts.authority.key(), SpinError::IncorrectSuperAdminOrAdmin);
+        require!(!accts.multisig.is_enabled, SpinError::MultisigRequired);
+
+        accts.pool.renounce_superadmin();
+        Ok(())
+    }
+
+    pub fn set_multisig(ctx: Context<SetMultisig>, _wheel_id: u64, signer_list: Vec<Pubkey>, threshold: u8, is_enabled: bool) -> Result<()> {
+        // once enabled, the signer set can only be changed by a proposal
+        require!(!ctx.accounts.multisig.is_enabled, SpinError::MultisigRequired);
+        ctx.accounts.multisig.set_signers(&signer_list, threshold, is_enabled)?;
+        Ok(())
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, _wheel_id: u64, proposal_id: u64, action: MultisigAction) -> Result<()> {
+        let accts = ctx.accounts;
+        let multisig = &mut accts.multisig;
+        require!(multisig.is_enabled, SpinError::MultisigDisabled);
+        require!(proposal_id == multisig.proposal_count, SpinError::IncorrectProposalAction);
+
+        let proposal = &mut accts.proposal;
+        proposal.pool = accts.pool.key();
+        proposal.proposer = accts.proposer.key();
+        proposal.proposal_id = proposal_id;
+        proposal.version = multisig.version;
+        proposal.action = action;
+        proposal.approve(multisig, &accts.proposer.key())?;
+
+        multisig.proposal_count += 1;
+
+        Ok(())
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, _wheel_id: u64, _proposal_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        require!(accts.multisig.is_enabled, SpinError::MultisigDisabled);
+        accts.proposal.approve(&accts.multisig, &accts.signer.key())?;
+        Ok(())
+    }
+
+    pub fn execute_config_proposal(ctx: Context<ExecuteConfigProposal>, _wheel_id: u64, _proposal_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.multisig.require_approved(&accts.proposal)?;
+
+        match accts.proposal.action {
+            MultisigAction::SetItem { index, item_mint_list, count, mint_amount_list, token_type, ratio, amount, item_count } => {
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.items.set_spinitem(index, ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+                        pending.items.set_count(item_count)
+                    })?;
+                } else {
+                    let mut state = accts.state.load_mut()?;
+                    state.set_spinitem(index, ItemRewardMints{item_mint_list, amount_list: mint_amount_list, count}, token_type, ratio, amount)?;
+                    state.set_count(item_count)?;
+                    state.validate(&accts.pool, &accts.pay_token_list)?;
+                }
+            }
+            MultisigAction::SetPayInfo { sol_price } => {
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.sol_price = sol_price;
+                        Ok(())
+                    })?;
+                } else {
+                    accts.pool.sol_price = sol_price;
+                    accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
+                }
+            }
+            MultisigAction::SetConfigDelay { config_delay } => {
+                set_config_delay_checked(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &mut accts.pool, &accts.pay_token_list, config_delay)?;
+            }
+            MultisigAction::ProposeSuperadmin { new_superadmin } => {
+                require!(new_superadmin != Pubkey::default(), SpinError::IncorrectSuperAdminOrAdmin);
+                accts.pool.pending_superadmin = new_superadmin;
+            }
+            MultisigAction::RenounceSuperadmin => accts.pool.renounce_superadmin(),
+            MultisigAction::SetBulkDiscounts { min_count_list, discount_bps_list, tier_count } => {
+                require!(tier_count as usize <= MAX_DISCOUNT_TIER_COUNT, SpinError::InvalidBulkDiscount);
+                let (min_count_list, discount_bps_list) = (&min_count_list[..tier_count as usize], &discount_bps_list[..tier_count as usize]);
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.set_bulk_discounts(min_count_list, discount_bps_list)
+                    })?;
+                } else {
+                    accts.pool.set_bulk_discounts(min_count_list, discount_bps_list)?;
+                    accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
+                }
+            }
+            MultisigAction::SetFreeSpinPeriod { free_spin_period } => {
+                require!(free_spin_period >= 0, SpinError::InvalidFreeSpinPeriod);
+                accts.pool.free_spin_period = free_spin_period;
+            }
+            MultisigAction::SetJackpot { jackpot_bps } => {
+                require!(jackpot_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.jackpot_bps = jackpot_bps;
+                        Ok(())
+                    })?;
+                } else {
+                    accts.pool.jackpot_bps = jackpot_bps;
+                    accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
+                }
+            }
+            MultisigAction::SetWheelLimits { ratio_total, max_rtp_bps } => {
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.ratio_total = ratio_total;
+                        pending.max_rtp_bps = max_rtp_bps;
+                        Ok(())
+                    })?;
+                } else {
+                    accts.pool.ratio_total = ratio_total;
+                    accts.pool.max_rtp_bps = max_rtp_bps;
+                    accts.state.load()?.validate(&accts.pool, &accts.pay_token_list)?;
+                }
+            }
+            MultisigAction::SetTreasuryDestination { destination, is_allowed } => {
+                accts.treasury.set_destination(destination, is_allowed)?;
+            }
+            MultisigAction::SetTreasuryCap { mint, epoch_cap } => accts.treasury.set_cap(mint, epoch_cap)?,
+            MultisigAction::SetRevenueSplit { sol_fee_bps, recipient_list, share_list, split_count } => {
+                require!(sol_fee_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+                require!(split_count as usize <= MAX_SPLIT_RECIPIENT_COUNT, SpinError::InvalidRevenueSplit);
+                accts.pool.sol_fee_bps = sol_fee_bps;
+                accts.pool.set_revenue_split(&recipient_list[..split_count as usize], &share_list[..split_count as usize])?;
+            }
+            MultisigAction::SetPayTokenFee { mint, fee_bps } => {
+                require!(mint != SOL_PAY_MINT, SpinError::IncorrectPayMint);
+                require!(fee_bps <= BPS_DENOMINATOR, SpinError::InvalidFeeRate);
+                accts.pay_token_list.get_or_add_pay_token(mint, fee_bps)?.fee_bps = fee_bps;
+            }
+            MultisigAction::AddAdmin { admin, roles } => accts.admin_info.add_admin(admin, roles)?,
+            MultisigAction::DeleteAdmin { admin } => accts.admin_info.delete_admin(admin)?,
+            MultisigAction::GrantRole { admin, roles } => accts.admin_info.grant_role(admin, roles)?,
+            MultisigAction::RevokeRole { admin, roles } => accts.admin_info.revoke_role(admin, roles)?,
+            MultisigAction::SetMultisig { signer_list, signer_count, threshold, is_enabled } => {
+                require!(signer_count as usize <= MAX_MULTISIG_SIGNER_COUNT, SpinError::InvalidMultisig);
+                accts.multisig.set_signers(&signer_list[..signer_count as usize], threshold, is_enabled)?;
+            }
+            MultisigAction::AddOracle { oracle } => accts.pool.add_oracle(oracle)?,
+            MultisigAction::DeleteOracle { oracle } => accts.pool.delete_oracle(oracle)?,
+            MultisigAction::RestockItem { index, stock } => {
+                require!(index < accts.state.load()?.count, SpinError::IndexOverflowSetItem);
+                if accts.pool.config_delay > 0 {
+                    stage_config_change(&mut *accts.pending_config.load_mut()?, &*accts.state.load()?, &accts.pool, &accts.pay_token_list, |pending| {
+                        pending.restock_list[index as usize] = stock;
+                        pending.is_restock_list[index as usize] = 1;
+                        Ok(())
+                    })?;
+                } else {
+                    accts.state.load_mut()?.stock_list[index as usize] = stock;
+                }
+            }
+            _ => return err!(SpinError::IncorrectProposalAction),
+        }
+
+        Ok(())
+    }
+
+    pub fn execute_withdraw_sol_proposal(ctx: Context<ExecuteWithdrawSolProposal>, wheel_id: u64, _proposal_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.multisig.require_approved(&accts.proposal)?;
+        accts.pool.require_unpaused(PAUSE_WITHDRAW)?;
+
+        let amount = match accts.proposal.action {
+            MultisigAction::WithdrawSol { destination, amount } => {
+                require!(destination == accts.dest_account.key(), SpinError::IncorrectProposalAction);
+                amount
+            }
+            _ => return err!(SpinError::IncorrectProposalAction),
+        };
+        accts.treasury.record_withdrawal(&accts.dest_account.key(), &SOL_PAY_MINT, amount, Clock::get()?.epoch)?;
+        require_sol_reserves(&accts.vault, &*accts.liabilities.load()?, amount)?;
+
+        transfer_sol_from_vault(
+            &accts.vault,
+            &accts.dest_account,
+            &accts.system_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("vault").unwrap(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn execute_withdraw_token_proposal(ctx: Context<ExecuteWithdrawTokenProposal>, wheel_id: u64, _proposal_id: u64) -> Result<()> {
+        let accts = ctx.accounts;
+        accts.multisig.require_approved(&accts.proposal)?;
+        accts.pool.require_unpaused(PAUSE_WITHDRAW)?;
+
+        let amount = match accts.proposal.action {
+            MultisigAction::WithdrawToken { source, destination, amount } => {
+                require!(
+                    source == accts.source_account.key() && destination == accts.dest_account.key(),
+                    SpinError::IncorrectProposalAction
+                );
+                amount
+            }
+            _ => return err!(SpinError::IncorrectProposalAction),
+        };
+        accts.treasury.record_withdrawal(&accts.dest_account.owner, &accts.source_account.mint, amount, Clock::get()?.epoch)?;
+        require_token_reserves(&accts.source_account, &*accts.liabilities.load()?, amount)?;
+
+        transfer_token_from_pool(
+            &accts.pool.to_account_info(),
+            &accts.source_account.to_account_info(),
+            &accts.dest_account.to_account_info(),
+            &accts.token_program.to_account_info(),
+            wheel_id,
+            *ctx.bumps.get("pool").unwrap(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct Initialize<'info> {
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut, signer)]
+    pub initializer: AccountInfo<'info>,
+
+    #[account(init, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<Pool>() + 8)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(init, seeds=[LAST_USERS_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<LatestUsers>() + 8)]
+    pub last_users : Box<Account<'info, LatestUsers>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub super_admin: AccountInfo<'info>,
+
+    #[account(init, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<SpinItemList>() + 8)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(init, seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<AdminInfo>() + 8)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(init, seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<PayTokenList>() + 8)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(init, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<Treasury>() + 8)]
+    pub treasury : Box<Account<'info, Treasury>>,
+
+    #[account(init, seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<Multisig>() + 8)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(init, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<PendingWheelConfig>() + 8)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+
+    #[account(init, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<Liabilities>() + 8)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(init, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump, payer=initializer, space=size_of::<Jackpot>() + 8)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault, funded to rent exemption here
+    pub jackpot_vault: AccountInfo<'info>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub system_program: Program<'info, System>,
+}
+
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetPayInfo<'info> {
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Account<'info, AdminInfo>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetRevenueSplit<'info> {
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetBulkDiscounts<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetJackpot<'info> {
+    #[account(mut)]
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault
+    pub jackpot_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetPayToken<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Account<'info, AdminInfo>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+
+    pub pay_mint: Box<Account<'info, Mint>>,
+
+    // vault that receives payments in pay_mint
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = pay_mint,
+        associated_token::authority = pool
+    )]
+    pub pay_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
+
+    // jackpot share of payments in pay_mint
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = pay_mint,
+        associated_token::authority = jackpot_vault
+    )]
+    pub jackpot_token_vault: Box<Account<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetWheelLimits<'info> {
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SpinWheel<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserState>()
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        init,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<SpinRequest>()
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, &wheel_id.to_le_bytes()],
+        bump
+    )]
+    /// CHECK: this should be checked with address in pool
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault, also the authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
+
+    // revenue split recipients follow in remaining_accounts, in pool order
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameSol<'info> {
+    // Charges pay_amount lamports: revenue split, jackpot share, rest into the vault.
+    // Returns what went into the vault.
+    fn pay(&mut self, recipients: &[AccountInfo<'info>], pay_amount: u64) -> Result<u64> {
+        let sol_fee = dev_fee(pay_amount, self.pool.sol_fee_bps)?;
+        let paid_fee = split_sol_fee(
+            &self.pool,
+            &self.user.to_account_info(),
+            recipients,
+            &self.system_program.to_account_info(),
+            sol_fee,
+            &[],
+        )?;
+        let jackpot_cut = dev_fee(pay_amount, self.pool.jackpot_bps)?;
+        if jackpot_cut > 0 {
+            invoke(
+                &system_instruction::transfer(&self.user.key(), &self.jackpot_vault.key(), jackpot_cut),
+                &[
+                    self.user.to_account_info().clone(),
+                    self.jackpot_vault.clone(),
+                    self.system_program.to_account_info().clone(),
+                ],
+            )?;
+            self.jackpot.add_sol(jackpot_cut)?;
+        }
+        let real_sol = pay_amount
+            .checked_sub(paid_fee)
+            .and_then(|amount| amount.checked_sub(jackpot_cut))
+            .ok_or(SpinError::InvalidFeeRate)?;
+
+        invoke(
+            &system_instruction::transfer(&self.user.key(), &self.vault.key(), real_sol),
+            &[
+                self.user.to_account_info().clone(),
+                self.vault.clone(),
+                self.system_program.to_account_info().clone(),
+            ],
+        )?;
+
+        Ok(real_sol)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameToken<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserState>()
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        init,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<SpinRequest>()
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    // pay mint
+    pub pay_mint: Box<Account<'info, Mint>>,
+
+    // pay vault created by set_pay_token
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = pool
+    )]
+    pub pay_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
+
+    // jackpot vault created by set_pay_token
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = jackpot_vault
+    )]
+    pub jackpot_token_vault: Box<Account<'info, TokenAccount>>,
+
+    // source account
+    #[account(mut, token::mint = pay_mint, token::authority = user)]
+    pub source_account: Box<Account<'info, TokenAccount>>,
+
+    // revenue split recipients' pay_mint ATAs follow in remaining_accounts, in pool order
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameToken<'info> {
+    // Charges `price` (REWARD_TOKEN_DECIMAL units) in pay_mint: revenue split,
+    // jackpot share, rest into the pay vault. Returns what went into the pay vault.
+    fn pay(&mut self, recipients: &[AccountInfo<'info>], pay_token: &PayToken, price: u64) -> Result<u64> {
+        let token_amount = to_mint_amount(price, pay_token.decimals)?;
+        let token_fee = dev_fee(token_amount, pay_token.fee_bps)?;
+        let paid_fee = split_token_fee(
+            &self.pool,
+            &pay_token.mint,
+            &self.source_account.to_account_info(),
+            &self.user.to_account_info(),
+            recipients,
+            &self.token_program.to_account_info(),
+            token_fee,
+            &[],
+        )?;
+        let jackpot_cut = dev_fee(token_amount, self.pool.jackpot_bps)?;
+        if jackpot_cut > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: self.source_account.to_account_info(),
+                    to: self.jackpot_token_vault.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            );
+            anchor_spl::token::transfer(cpi_ctx, jackpot_cut)?;
+            self.jackpot.add(&pay_token.mint, jackpot_cut)?;
+        }
+        let real_token = token_amount
+            .checked_sub(paid_fee)
+            .and_then(|amount| amount.checked_sub(jackpot_cut))
+            .ok_or(SpinError::InvalidFeeRate)?;
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.source_account.to_account_info(),
+                to: self.pay_vault.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer(cpi_ctx, real_token)?;
+
+        Ok(real_token)
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserState<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [USER_STATE_SEED, user.key().as_ref()], bump)]
+    /// CHECK: may still have an older, shorter layout
+    pub user_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ManageCreditSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserState>()
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(mut, seeds = [VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: this should be checked with address in pool
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ManageCreditToken<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserState>()
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    pub pay_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = pool
+    )]
+    pub pay_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = pay_mint, token::authority = user)]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameCreditSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds = [USER_STATE_SEED, user.key().as_ref()], bump)]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        init,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<SpinRequest>()
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(mut, seeds = [VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: this should be checked with address in pool
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault
+    pub jackpot_vault: AccountInfo<'info>,
+
+    // revenue split recipients follow in remaining_accounts, in pool order
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameCreditSol<'info> {
+    // Same split as PlayGameSol::pay, but the credited lamports already sit in the vault.
+    fn spend(&mut self, recipients: &[AccountInfo<'info>], wheel_id: u64, vault_bump: u8, pay_amount: u64) -> Result<u64> {
+        self.user_state.spend_credit(&self.pool.key(), &SOL_PAY_MINT, pay_amount)?;
+        self.liabilities.load_mut()?.sub_sol(pay_amount);
+
+        let wheel_id_bytes = wheel_id.to_le_bytes();
+        let vault_seeds = &[VAULT_SEED, &wheel_id_bytes[..], &[vault_bump]];
+        let sol_fee = dev_fee(pay_amount, self.pool.sol_fee_bps)?;
+        let paid_fee = split_sol_fee(
+            &self.pool,
+            &self.vault,
+            recipients,
+            &self.system_program.to_account_info(),
+            sol_fee,
+            &[&vault_seeds[..]],
+        )?;
+        let jackpot_cut = dev_fee(pay_amount, self.pool.jackpot_bps)?;
+        let real_sol = pay_amount
+            .checked_sub(paid_fee)
+            .and_then(|amount| amount.checked_sub(jackpot_cut))
+            .ok_or(SpinError::InvalidFeeRate)?;
+        if jackpot_cut > 0 {
+            transfer_sol_from_vault(
+                &self.vault,
+                &self.jackpot_vault,
+                &self.system_program.to_account_info(),
+                wheel_id,
+                vault_bump,
+                jackpot_cut,
+            )?;
+            self.jackpot.add_sol(jackpot_cut)?;
+        }
+
+        Ok(real_sol)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameCreditToken<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds = [USER_STATE_SEED, user.key().as_ref()], bump)]
+    pub user_state: Box<Account<'info, UserState>>,
 
     #[account(
         init,
-        seeds = [&round_id.to_le_bytes(), user.key().as_ref()],
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
         bump,
         payer = user,
-        space = 8 + size_of::<UserPendingClaimState>()
+        space = 8 + size_of::<SpinRequest>()
     )]
-    pub user_pendingstate: Box<Account<'info, UserPendingClaimState>>,
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    pub pay_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = pool
+    )]
+    pub pay_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = jackpot_vault
+    )]
+    pub jackpot_token_vault: Box<Account<'info, TokenAccount>>,
+
+    // revenue split recipients' pay_mint ATAs follow in remaining_accounts, in pool order
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameCreditToken<'info> {
+    // Same split as PlayGameToken::pay, out of the credited tokens in the pay vault.
+    fn spend(&mut self, recipients: &[AccountInfo<'info>], wheel_id: u64, pool_bump: u8, pay_token: &PayToken, price: u64) -> Result<u64> {
+        let token_amount = to_mint_amount(price, pay_token.decimals)?;
+        self.user_state.spend_credit(&self.pool.key(), &pay_token.mint, token_amount)?;
+        self.liabilities.load_mut()?.sub(&pay_token.mint, token_amount);
+
+        let wheel_id_bytes = wheel_id.to_le_bytes();
+        let authority_seeds = &[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes, &[pool_bump]];
+        let token_fee = dev_fee(token_amount, pay_token.fee_bps)?;
+        let paid_fee = split_token_fee(
+            &self.pool,
+            &pay_token.mint,
+            &self.pay_vault.to_account_info(),
+            &self.pool.to_account_info(),
+            recipients,
+            &self.token_program.to_account_info(),
+            token_fee,
+            &[&authority_seeds[..]],
+        )?;
+        let jackpot_cut = dev_fee(token_amount, self.pool.jackpot_bps)?;
+        let real_token = token_amount
+            .checked_sub(paid_fee)
+            .and_then(|amount| amount.checked_sub(jackpot_cut))
+            .ok_or(SpinError::InvalidFeeRate)?;
+        if jackpot_cut > 0 {
+            transfer_token_from_pool(
+                &self.pool.to_account_info(),
+                &self.pay_vault.to_account_info(),
+                &self.jackpot_token_vault.to_account_info(),
+                &self.token_program.to_account_info(),
+                wheel_id,
+                pool_bump,
+                jackpot_cut,
+            )?;
+            self.jackpot.add(&pay_token.mint, jackpot_cut)?;
+        }
+
+        Ok(real_token)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct InitTicketMint<'info> {
+    #[account(mut)]
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        seeds = [TICKET_MINT_SEED, &wheel_id.to_le_bytes()],
+        bump,
+        payer = superadmin,
+        mint::decimals = 0,
+        mint::authority = pool,
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct MintTickets<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds = [TICKET_MINT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub ticket_mint: Box<Account<'info, Mint>>,
 
-    // dust mint
+    #[account(mut, token::mint = ticket_mint)]
+    pub dest_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameTicket<'info> {
     #[account(mut)]
-    pub dust_mint: Box<Account<'info, Mint>>,
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
 
-    // dust vault that holds the dust mint for distribution
     #[account(
         init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
         payer = user,
-        associated_token::mint = dust_mint,
-        associated_token::authority = pool
+        space = 8 + size_of::<UserState>()
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        init,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<SpinRequest>()
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(mut, seeds = [TICKET_MINT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = user)]
+    pub ticket_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct InitFreeSpinItems<'info> {
+    #[account(mut)]
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
     )]
-    pub dust_vault: Box<Account<'info, TokenAccount>>,
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(init, seeds=[FREE_SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump, payer=superadmin, space=size_of::<SpinItemList>() + 8)]
+    pub free_state : AccountLoader<'info, SpinItemList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetFreeSpinItem<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[FREE_SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub free_state : AccountLoader<'info, SpinItemList>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetFreeSpinPeriod<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
 
-    // forge mint
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, commitment: [u8; 32], round_id : u64)]
+pub struct PlayGameFree<'info> {
     #[account(mut)]
-    pub forge_mint: Box<Account<'info, Mint>>,
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
 
-    // forge vault that holds the pay mint for distribution
     #[account(
         init_if_needed,
+        seeds = [USER_STATE_SEED, user.key().as_ref()],
+        bump,
         payer = user,
-        associated_token::mint = forge_mint,
-        associated_token::authority = pool
+        space = 8 + size_of::<UserState>()
     )]
-    pub forge_vault: Box<Account<'info, TokenAccount>>,
+    pub user_state: Box<Account<'info, UserState>>,
 
-    // source account
+    #[account(
+        init,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<SpinRequest>()
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    // settlement draws from this list, it has to exist before free spins are requested
+    #[account(seeds=[FREE_SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub free_state : AccountLoader<'info, SpinItemList>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, round_id : u64)]
+pub struct SettleSpin<'info> {
     #[account(mut)]
-    /// CHECK: this should be checked with address in pool
-    pub source_account: AccountInfo<'info>,
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LAST_USERS_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub last_users : Box<Account<'info, LatestUsers>>,
+
+    #[account(mut, address = spin_items_address(wheel_id, spin_request.is_free))]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(mut, seeds = [VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL vault, receives jackpot wins
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault, also the authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [VAULT_SEED],
-        bump
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        constraint = spin_request.user == user.key(),
+        constraint = spin_request.pool == pool.key(),
+        close = user
     )]
-    /// CHECK: this should be checked with address in pool
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(
+        init,
+        seeds = [PENDING_CLAIM_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserPendingClaimState>()
+    )]
+    pub user_pendingstate: Box<Account<'info, UserPendingClaimState>>,
+
+    /// CHECK: raw SlotHashes data is parsed in get_slot_hash
+    #[account(address = sysvar::slot_hashes::id())]
+    pub slot_hashes: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, round_id : u64)]
+pub struct RequestRandomness<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        constraint = spin_request.use_oracle @ SpinError::IncorrectRandomnessSource
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(
+        init,
+        seeds = [RANDOMNESS_REQUEST_SEED, spin_request.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<RandomnessRequest>()
+    )]
+    pub randomness_request: Box<Account<'info, RandomnessRequest>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillRandomness<'info> {
+    pub oracle: Signer<'info>,
+
+    #[account(address = randomness_request.pool)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        constraint = !randomness_request.is_fulfilled @ SpinError::RandomnessAlreadyFulfilled
+    )]
+    pub randomness_request: Box<Account<'info, RandomnessRequest>>,
+
+    /// CHECK: the ed25519 proof is read from the instructions sysvar
+    #[account(address = sysvar::instructions::id())]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, round_id : u64)]
+pub struct SettleOracleSpin<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LAST_USERS_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub last_users : Box<Account<'info, LatestUsers>>,
+
+    #[account(mut, address = spin_items_address(wheel_id, spin_request.is_free))]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(mut, seeds = [VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL vault, receives jackpot wins
     pub vault: AccountInfo<'info>,
 
+    #[account(mut, seeds=[JACKPOT_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub jackpot : Box<Account<'info, Jackpot>>,
+
+    #[account(mut, seeds = [JACKPOT_VAULT_SEED, &wheel_id.to_le_bytes()], bump)]
+    /// CHECK: SOL jackpot vault, also the authority of the jackpot token vaults
+    pub jackpot_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        constraint = spin_request.user == user.key(),
+        constraint = spin_request.pool == pool.key(),
+        close = user
+    )]
+    pub spin_request: Box<Account<'info, SpinRequest>>,
+
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_REQUEST_SEED, spin_request.key().as_ref()],
+        bump,
+        constraint = randomness_request.is_fulfilled @ SpinError::RandomnessNotFulfilled,
+        close = user
+    )]
+    pub randomness_request: Box<Account<'info, RandomnessRequest>>,
+
+    #[account(
+        init,
+        seeds = [PENDING_CLAIM_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + size_of::<UserPendingClaimState>()
+    )]
+    pub user_pendingstate: Box<Account<'info, UserPendingClaimState>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, round_id : u64)]
+pub struct CloseExpiredSpin<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds = [USER_STATE_SEED, user.key().as_ref()], bump)]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
     #[account(
         mut,
-        address = pool.dev_wallet
+        seeds = [SPIN_REQUEST_SEED, &wheel_id.to_le_bytes(), &round_id.to_le_bytes(), user.key().as_ref()],
+        bump,
+        constraint = spin_request.user == user.key(),
+        constraint = spin_request.pool == pool.key(),
+        close = user
     )]
-    /// CHECK: this should be checked with sol vault address
-    pub dev_account: AccountInfo<'info>,
-
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+    pub spin_request: Box<Account<'info, SpinRequest>>,
 
-    /// CHECK: We're reading data from this chainlink feed account
-    pub pyth_account: AccountInfo<'info>,
+    #[account(mut, seeds = [RANDOMNESS_REQUEST_SEED, spin_request.key().as_ref()], bump)]
+    /// CHECK: the spin's randomness request, empty if it was never made
+    pub randomness_request: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(wheel_id: u64)]
 pub struct Claim<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(mut, signer)]
     pub owner : AccountInfo<'info>,
 
     /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut, seeds=[ESCROW_PDA_SEED.as_ref()], bump)]
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
     pub pool : Box<Account<'info, Pool>>,
 
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
     #[account(
         mut,
-        seeds = [VAULT_SEED],
+        seeds = [VAULT_SEED, &wheel_id.to_le_bytes()],
         bump
     )]
     /// CHECK: this should be checked with address in pool
@@ -516,7 +2527,8 @@ pub struct Claim<'info> {
 
     #[account(
         mut,
-        constraint = owner.key() == user_pendingstate.user
+        constraint = owner.key() == user_pendingstate.user,
+        constraint = pool.key() == user_pendingstate.pool
     )]
     pub user_pendingstate: Box<Account<'info, UserPendingClaimState>>,
 
@@ -534,7 +2546,7 @@ pub struct Claim<'info> {
 }
 
 impl<'info> Claim<'info> {
-    fn into_transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    fn transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         let cpi_accounts = Transfer {
             from: self
                 .source_reward_account
@@ -548,13 +2560,56 @@ impl<'info> Claim<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ClaimAll<'info> {
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut, signer)]
+    pub owner : AccountInfo<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, &wheel_id.to_le_bytes()],
+        bump
+    )]
+    /// CHECK: this should be checked with address in pool
+    pub vault: AccountInfo<'info>,
+
+    // everything is paid out, so the account goes back to the owner
+    #[account(
+        mut,
+        constraint = owner.key() == user_pendingstate.user,
+        constraint = pool.key() == user_pendingstate.pool,
+        close = owner
+    )]
+    pub user_pendingstate: Box<Account<'info, UserPendingClaimState>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
 pub struct CloseUserPendingAcc<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(mut, signer)]
     owner : AccountInfo<'info>,
 
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
     #[account(
         mut,
+        constraint = owner.key() == user_pendingstate.user,
+        constraint = pool.key() == user_pendingstate.pool,
         close = owner,
     )]
     pub user_pendingstate: Account<'info, UserPendingClaimState>,
@@ -562,72 +2617,419 @@ pub struct CloseUserPendingAcc<'info> {
 
 
 #[derive(Accounts)]
+#[instruction(wheel_id: u64)]
 pub struct Withdraw<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut, constraint = pool.superadmin == *authority.key)]
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
     pub pool : Box<Account<'info, Pool>>,
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut,owner=spl_token::id())]
-    pub source_account : AccountInfo<'info>,
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut,owner=spl_token::id())]
-    pub dest_account : AccountInfo<'info>,
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
+
+    #[account(mut)]
+    pub source_account : Box<Account<'info, TokenAccount>>,
+
+    // its owner has to be an allowlisted treasury destination
+    #[account(mut)]
+    pub dest_account : Box<Account<'info, TokenAccount>>,
 
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> Withdraw<'info> {
-    fn into_transfer_from_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self
-                .source_account
-                .to_account_info()
-                .clone(),
-            to: self.dest_account.to_account_info().clone(),
-            authority: self.pool.to_account_info().clone(),
-        };
-        CpiContext::new(self.token_program.to_account_info().clone(), cpi_accounts)
-    }
-}
-
 #[derive(Accounts)]
+#[instruction(wheel_id: u64)]
 pub struct WithdrawSol<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
 
     #[account(
         mut,
-        seeds = [VAULT_SEED],
+        seeds = [VAULT_SEED, &wheel_id.to_le_bytes()],
         bump
     )]
     /// CHECK: this should be checked with address in pool
     pub vault: AccountInfo<'info>,
 
     #[account(mut)]
-    /// CHECK: this should be checked with address in pool
+    /// CHECK: checked against the treasury destination allowlist
     pub dest_account: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ManageTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump, constraint = pool.superadmin == *authority.key)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
 pub struct ManageAdmin<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(constraint = pool.superadmin == *authority.key)]
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
     pub pool : Box<Account<'info, Pool>>,
 
-    #[account(mut)]
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
     pub admin_info : Account<'info, AdminInfo>,
 
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub admin : AccountInfo<'info>,
 }
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ManageOracle<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump, constraint = pool.superadmin == *authority.key)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub oracle : AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetMultisig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump, constraint = pool.superadmin == *authority.key)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, proposal_id: u64)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, &wheel_id.to_le_bytes(), &proposal_id.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = size_of::<Proposal>() + 8
+    )]
+    pub proposal : Box<Account<'info, Proposal>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, proposal_id: u64)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds=[PROPOSAL_SEED, &wheel_id.to_le_bytes(), &proposal_id.to_le_bytes()], bump)]
+    pub proposal : Box<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, proposal_id: u64)]
+pub struct ExecuteConfigProposal<'info> {
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, &wheel_id.to_le_bytes(), &proposal_id.to_le_bytes()],
+        bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal : Box<Account<'info, Proposal>>,
+
+    /// CHECK: receives the proposal rent back
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, proposal_id: u64)]
+pub struct ExecuteWithdrawSolProposal<'info> {
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, &wheel_id.to_le_bytes(), &proposal_id.to_le_bytes()],
+        bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal : Box<Account<'info, Proposal>>,
+
+    /// CHECK: receives the proposal rent back
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, &wheel_id.to_le_bytes()],
+        bump
+    )]
+    /// CHECK: this should be checked with address in pool
+    pub vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: checked against the proposal and the treasury destination allowlist
+    pub dest_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64, proposal_id: u64)]
+pub struct ExecuteWithdrawTokenProposal<'info> {
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[LIABILITIES_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub liabilities : AccountLoader<'info, Liabilities>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, &wheel_id.to_le_bytes(), &proposal_id.to_le_bytes()],
+        bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal : Box<Account<'info, Proposal>>,
+
+    /// CHECK: receives the proposal rent back
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+
+    #[account(mut, seeds=[TREASURY_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub treasury : Box<Account<'info, Treasury>>,
+
+    #[account(mut)]
+    pub source_account : Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub dest_account : Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetConfigDelay<'info> {
+    pub superadmin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()],
+        bump,
+        constraint = pool.superadmin == superadmin.key(),
+    )]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct StageConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    // written directly by set_item_stock when there is no timelock
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct ActivateConfig<'info> {
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct TransferSuperadmin<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(mut, seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct SetItemPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+}
+
+#[derive(Accounts)]
+#[instruction(wheel_id: u64)]
+pub struct RestockItem<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds=[ESCROW_PDA_SEED.as_bytes(), &wheel_id.to_le_bytes()], bump)]
+    pub pool : Box<Account<'info, Pool>>,
+
+    #[account(seeds=[MULTISIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub multisig : Box<Account<'info, Multisig>>,
+
+    #[account(seeds=[ADMIN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub admin_info : Box<Account<'info, AdminInfo>>,
+
+    #[account(mut, seeds=[SPIN_ITEMS_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub state : AccountLoader<'info, SpinItemList>,
+
+    #[account(mut, seeds=[PENDING_CONFIG_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pending_config : AccountLoader<'info, PendingWheelConfig>,
+
+    #[account(seeds=[PAY_TOKEN_LIST_SEED, &wheel_id.to_le_bytes()], bump)]
+    pub pay_token_list : Box<Account<'info, PayTokenList>>,
+}
@@ -1,9 +1,746 @@
-use crate::{account::*};
+use crate::{account::*, constants::*, errors::*};
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+use solana_program::{ed25519_program, hash, instruction::Instruction, program::invoke, program::invoke_signed, system_instruction};
 
 
-pub fn dev_fee(pool: &Pool, amount: u64) -> Result<u64> {
-    let fee = pool.dev_fee as u128;
-    let res = (amount as u128) * fee / 100;
+pub fn dev_fee(amount: u64, fee_bps: u64) -> Result<u64> {
+    let fee = fee_bps as u128;
+    let res = (amount as u128) * fee / BPS_DENOMINATOR as u128;
     Ok(res as u64)
 }
+
+// SlotHashes is too large to deserialize on-chain, so walk the raw entries
+// (newest first) and take the first slot at or after target_slot.
+pub fn get_slot_hash(slot_hashes: &AccountInfo, target_slot: u64) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let len = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
+
+    let mut found: Option<[u8; 32]> = None;
+    for i in 0..len {
+        let offset = 8 + i * 40;
+        let slot = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        if slot < target_slot {
+            // an older entry proves nothing between target_slot and `found` was evicted
+            return found.ok_or_else(|| error!(SpinError::SpinNotReady));
+        }
+        found = Some(data[offset + 8..offset + 40].try_into().unwrap());
+        if slot == target_slot {
+            return Ok(found.unwrap());
+        }
+    }
+
+    match found {
+        Some(_) => err!(SpinError::SpinRequestExpired),
+        None => err!(SpinError::SpinNotReady),
+    }
+}
+
+// Pays each revenue split recipient its share of `fee` from `payer`.
+// `recipients` are the recipients' accounts in pool order; returns the total paid.
+pub fn split_sol_fee<'info>(
+    pool: &Pool,
+    payer: &AccountInfo<'info>,
+    recipients: &[AccountInfo<'info>],
+    system_program: &AccountInfo<'info>,
+    fee: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    require!(recipients.len() >= pool.split_count as usize, SpinError::IncorrectSplitRecipient);
+
+    let mut paid = 0;
+    for (i, recipient) in recipients.iter().take(pool.split_count as usize).enumerate() {
+        require!(recipient.key().eq(&pool.split_recipient_list[i]), SpinError::IncorrectSplitRecipient);
+
+        let share = dev_fee(fee, pool.split_share_list[i])?;
+        if share == 0 {
+            continue;
+        }
+        invoke_signed(
+            &system_instruction::transfer(&payer.key(), &recipient.key(), share),
+            &[payer.clone(), recipient.clone(), system_program.clone()],
+            signer_seeds,
+        )?;
+        paid += share;
+    }
+
+    Ok(paid)
+}
+
+// Token version of split_sol_fee; `recipients` are the recipients' ATAs for `mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn split_token_fee<'info>(
+    pool: &Pool,
+    mint: &Pubkey,
+    source: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    recipients: &[AccountInfo<'info>],
+    token_program: &AccountInfo<'info>,
+    fee: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    require!(recipients.len() >= pool.split_count as usize, SpinError::IncorrectSplitRecipient);
+
+    let mut paid = 0;
+    for (i, recipient) in recipients.iter().take(pool.split_count as usize).enumerate() {
+        require!(
+            recipient.key().eq(&get_associated_token_address(&pool.split_recipient_list[i], mint)),
+            SpinError::IncorrectSplitRecipient
+        );
+
+        let share = dev_fee(fee, pool.split_share_list[i])?;
+        if share == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new(
+            token_program.clone(),
+            anchor_spl::token::Transfer {
+                from: source.clone(),
+                to: recipient.clone(),
+                authority: authority.clone(),
+            },
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer_seeds), share)?;
+        paid += share;
+    }
+
+    Ok(paid)
+}
+
+// Rewards are only paid from the pool's ATA for the mint, to a token account of the claimer.
+pub fn check_reward_accounts(
+    source_reward_account: &Account<TokenAccount>,
+    dest_reward_account: &Account<TokenAccount>,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<()> {
+    require!(
+        source_reward_account.key().eq(&get_associated_token_address(pool, mint)),
+        SpinError::IncorrectRewardVault
+    );
+    require!(
+        dest_reward_account.mint.eq(mint) && dest_reward_account.owner.eq(owner),
+        SpinError::InvalidReward
+    );
+
+    Ok(())
+}
+
+// The superadmin holds every role; admins need the role bit in their entry.
+pub fn require_role(pool: &Pool, admin_info: &AdminInfo, authority: &Pubkey, role: u8) -> Result<()> {
+    require!(
+        pool.superadmin.eq(authority) || admin_info.has_role(authority, role),
+        SpinError::IncorrectSuperAdminOrAdmin
+    );
+
+    Ok(())
+}
+
+// Admin managers may manage other admins, but only the superadmin may touch
+// admin-manager entries or hand out the admin-manager role.
+pub fn authorize_admin_change(pool: &Pool, admin_info: &AdminInfo, authority: &Pubkey, admin: &Pubkey, roles: u8) -> Result<()> {
+    require_role(pool, admin_info, authority, ROLE_ADMIN_MANAGER)?;
+
+    if !pool.superadmin.eq(authority) {
+        require!(
+            roles & ROLE_ADMIN_MANAGER == 0 && !admin_info.has_role(admin, ROLE_ADMIN_MANAGER),
+            SpinError::IncorrectSuperAdminOrAdmin
+        );
+    }
+
+    Ok(())
+}
+
+// Single authorization path for SOL and token withdrawals: the signer must be the
+// superadmin or a treasurer admin, the destination wallet must be allowlisted,
+// and the amount must fit the asset's remaining cap for this epoch.
+pub fn authorize_withdrawal(
+    pool: &Pool,
+    admin_info: &AdminInfo,
+    treasury: &mut Treasury,
+    authority: &Pubkey,
+    destination: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    require_role(pool, admin_info, authority, ROLE_TREASURER)?;
+
+    treasury.record_withdrawal(destination, mint, amount, Clock::get()?.epoch)
+}
+
+pub fn transfer_sol_from_vault<'info>(
+    vault: &AccountInfo<'info>,
+    dest: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    wheel_id: u64,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    invoke_signed(
+        &system_instruction::transfer(&vault.key(), &dest.key(), amount),
+        &[vault.clone(), dest.clone(), system_program.clone()],
+        &[&[VAULT_SEED, &wheel_id.to_le_bytes(), &[bump]]],
+    )?;
+
+    Ok(())
+}
+
+pub fn transfer_token_from_pool<'info>(
+    pool: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    dest: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    wheel_id: u64,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let wheel_id_bytes = wheel_id.to_le_bytes();
+    let authority_seeds = &[ESCROW_PDA_SEED.as_bytes(), &wheel_id_bytes, &[bump]];
+    let cpi_ctx = CpiContext::new(
+        token_program.clone(),
+        anchor_spl::token::Transfer {
+            from: source.clone(),
+            to: dest.clone(),
+            authority: pool.clone(),
+        },
+    );
+    anchor_spl::token::transfer(cpi_ctx.with_signer(&[&authority_seeds[..]]), amount)
+}
+
+// Applies a change to the staged wheel config and restarts its timelock.
+pub fn stage_config_change<F>(
+    pending: &mut PendingWheelConfig,
+    state: &SpinItemList,
+    pool: &Pool,
+    pay_token_list: &PayTokenList,
+    change: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut PendingWheelConfig) -> Result<()>,
+{
+    if pending.is_staged == 0 {
+        pending.stage_from(state, pool, pay_token_list);
+    }
+    change(pending)?;
+    pending.activates_at = Clock::get()?.unix_timestamp.checked_add(pool.config_delay).ok_or(SpinError::AmountOverflow)?;
+
+    pending.validate(pool, pay_token_list)
+}
+
+// A longer delay applies at once; a shorter one is staged, so it only counts
+// once the current delay has run out.
+pub fn set_config_delay_checked(
+    pending: &mut PendingWheelConfig,
+    state: &SpinItemList,
+    pool: &mut Pool,
+    pay_token_list: &PayTokenList,
+    config_delay: i64,
+) -> Result<()> {
+    require!(config_delay >= 0, SpinError::ConfigTimelocked);
+
+    if config_delay < pool.config_delay {
+        return stage_config_change(pending, state, pool, pay_token_list, |pending| {
+            pending.config_delay = config_delay;
+            Ok(())
+        });
+    }
+    pool.config_delay = config_delay;
+    // a staged config would otherwise put back the delay it was staged with
+    if pending.is_staged != 0 {
+        pending.config_delay = config_delay;
+    }
+
+    Ok(())
+}
+
+// Converts a price kept in REWARD_TOKEN_DECIMAL units to the mint's own units.
+pub fn to_mint_amount(amount: u64, decimals: u8) -> Result<u64> {
+    let amount = if decimals > REWARD_TOKEN_DECIMAL {
+        10u64
+            .checked_pow((decimals - REWARD_TOKEN_DECIMAL) as u32)
+            .and_then(|scale| amount.checked_mul(scale))
+    } else {
+        10u64
+            .checked_pow((REWARD_TOKEN_DECIMAL - decimals) as u32)
+            .map(|scale| amount / scale)
+    };
+
+    amount.ok_or_else(|| error!(SpinError::AmountOverflow))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn open_spin_request(
+    user_state: &mut UserState,
+    spin_request: &mut SpinRequest,
+    user: Pubkey,
+    pool: Pubkey,
+    commitment: [u8; 32],
+    pay_mint: Pubkey,
+    pay_amount: u64,
+    use_oracle: bool,
+    spin_count: u8,
+) -> Result<()> {
+    if user_state.is_initialized == 0 {
+        user_state.is_initialized = 1;
+        user_state.user = user;
+        user_state.round_num = 1;
+    } else {
+        require!(
+            user_state.user.eq(&user),
+            SpinError::IncorrectUserState
+        );
+        user_state.round_num += 1;
+    }
+
+    // the seed is revealed in settle_spin and mixed with the hash of a slot that doesn't exist yet
+    spin_request.user = user;
+    spin_request.pool = pool;
+    spin_request.round_num = user_state.round_num;
+    spin_request.commitment = commitment;
+    spin_request.target_slot = Clock::get()?.slot + SPIN_SETTLE_DELAY_SLOTS;
+    spin_request.pay_mint = pay_mint;
+    spin_request.pay_amount = pay_amount;
+    spin_request.use_oracle = use_oracle;
+    spin_request.spin_count = spin_count;
+
+    Ok(())
+}
+
+// SOL the vault can pay out while staying rent exempt.
+pub fn sol_vault_balance(vault: &AccountInfo) -> Result<u64> {
+    Ok(vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0)))
+}
+
+// Free spins draw from their own item list.
+pub fn spin_items_address(wheel_id: u64, is_free: bool) -> Pubkey {
+    let seed = if is_free { FREE_SPIN_ITEMS_SEED } else { SPIN_ITEMS_SEED };
+    Pubkey::find_program_address(&[seed, &wheel_id.to_le_bytes()], &crate::ID).0
+}
+
+// Tops a SOL PDA up to rent exemption, so transfers into it never fall below the minimum.
+pub fn fund_rent_exempt<'info>(payer: &AccountInfo<'info>, account: &AccountInfo<'info>, system_program: &AccountInfo<'info>) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(0).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(&payer.key(), &account.key(), shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn require_sol_reserves(vault: &AccountInfo, liabilities: &Liabilities, amount: u64) -> Result<()> {
+    let remaining = sol_vault_balance(vault)?.checked_sub(amount).ok_or(SpinError::InsufficientReserves)?;
+    require!(remaining >= liabilities.sol_amount, SpinError::InsufficientReserves);
+
+    Ok(())
+}
+
+pub fn require_token_reserves(source: &TokenAccount, liabilities: &Liabilities, amount: u64) -> Result<()> {
+    let remaining = source.amount.checked_sub(amount).ok_or(SpinError::InsufficientReserves)?;
+    require!(remaining >= liabilities.get(&source.mint), SpinError::InsufficientReserves);
+
+    Ok(())
+}
+
+// Whether the vaults can pay the item on top of what is already owed. Every
+// reward mint of the item needs the pool's vault among `reward_vaults`.
+fn item_is_covered(
+    state: &SpinItemList,
+    index: usize,
+    pool: &Pubkey,
+    liabilities: &Liabilities,
+    vault: &AccountInfo,
+    reward_vaults: &[AccountInfo],
+) -> Result<bool> {
+    // jackpot payouts are moved out of the jackpot vaults as they are won
+    if state.token_type_list[index] == TOKEN_TYPE_JACKPOT {
+        return Ok(true);
+    }
+
+    let amount = state.amount_list[index];
+    if state.token_type_list[index] == 2 && amount > 0
+        && liabilities.sol_amount.checked_add(amount).ok_or(SpinError::AmountOverflow)? > sol_vault_balance(vault)? {
+        return Ok(false);
+    }
+
+    let reward_mints = state.reward_mint_list[index];
+    let mint_list = reward_mints.item_mint_list;
+    let mint_amount_list = reward_mints.amount_list;
+    for (mint, mint_amount) in mint_list.iter().zip(mint_amount_list.iter()).take(reward_mints.count as usize) {
+        if *mint_amount == 0 {
+            continue;
+        }
+        let vault_key = get_associated_token_address(pool, mint);
+        let reward_vault = reward_vaults
+            .iter()
+            .find(|account| account.key() == vault_key)
+            .ok_or(SpinError::IncorrectRewardVault)?;
+        let balance = Account::<TokenAccount>::try_from(reward_vault)?.amount;
+        if liabilities.get(mint).checked_add(*mint_amount).ok_or(SpinError::AmountOverflow)? > balance {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// Draws every result of the spin request into the pending claim account. Jackpot
+// hits come back as payouts that still have to be moved into the pool vaults.
+#[allow(clippy::too_many_arguments)]
+pub fn record_spin_result(
+    pool: &Account<Pool>,
+    state: &mut SpinItemList,
+    liabilities: &mut Liabilities,
+    jackpot: &mut Jackpot,
+    user_pendingstate: &mut UserPendingClaimState,
+    last_users: &mut LatestUsers,
+    spin_request: &SpinRequest,
+    vault: &AccountInfo,
+    jackpot_vault: &AccountInfo,
+    reward_vaults: &[AccountInfo],
+    seed: [u8; 32],
+) -> Result<Vec<JackpotPayout>> {
+    require!(state.total_ratio() == pool.ratio_total, SpinError::SpinWeightsIncomplete);
+
+    user_pendingstate.user = spin_request.user;
+    user_pendingstate.pool = spin_request.pool;
+    user_pendingstate.is_claimed = 0;
+    user_pendingstate.round_num = spin_request.round_num;
+
+    let mut flags = if spin_request.is_ticket { LATEST_FLAG_TICKET } else { 0 };
+    if spin_request.is_free {
+        flags |= LATEST_FLAG_FREE;
+    }
+    let spin_count = spin_request.spin_count.max(1);
+    let pay_amount = spin_request.pay_amount / spin_count as u64;
+
+    // results after the first draw from independent seeds derived from the same randomness
+    let mut jackpot_payouts = vec![];
+    for i in 0..spin_count {
+        let draw_seed = if i == 0 { seed } else { hash::hashv(&[&seed, &[i]]).to_bytes() };
+        let jackpot_payout = record_one_result(
+            pool,
+            state,
+            liabilities,
+            jackpot,
+            user_pendingstate,
+            last_users,
+            spin_request.user,
+            pay_amount,
+            flags,
+            vault,
+            jackpot_vault,
+            reward_vaults,
+            draw_seed,
+        )?;
+        if let Some(payout) = jackpot_payout {
+            jackpot_payouts.push(payout);
+        }
+    }
+
+    Ok(jackpot_payouts)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_one_result(
+    pool: &Account<Pool>,
+    state: &mut SpinItemList,
+    liabilities: &mut Liabilities,
+    jackpot: &mut Jackpot,
+    user_pendingstate: &mut UserPendingClaimState,
+    last_users: &mut LatestUsers,
+    user: Pubkey,
+    pay_amount: u64,
+    mut flags: u8,
+    vault: &AccountInfo,
+    jackpot_vault: &AccountInfo,
+    reward_vaults: &[AccountInfo],
+    seed: [u8; 32],
+) -> Result<Option<JackpotPayout>> {
+    // items the vaults can't cover are skipped and the draw is repeated over the rest;
+    // the spin is rejected when none is left, the item config itself is never touched
+    let mut skip_mask = 0u16;
+    loop {
+        state.get_spinresult(seed, skip_mask)?;
+        let index = state.last_spinindex as usize;
+        if item_is_covered(state, index, &pool.key(), liabilities, vault, reward_vaults)? {
+            break;
+        }
+        msg!("spin item {} skipped, reserves can't cover it", index);
+        skip_mask |= 1 << index;
+    }
+
+    let index = state.last_spinindex as usize;
+    if state.has_stock_limit_list[index] != 0 {
+        state.stock_list[index] -= 1;
+    }
+
+    let token_type = state.token_type_list[index];
+    let reward_mints = state.reward_mint_list[index];
+    let mut mint_amount_list = reward_mints.amount_list;
+    let mut sol_amount = 0;
+    let mut jackpot_payout = None;
+    if token_type == TOKEN_TYPE_JACKPOT {
+        let payout = jackpot.take_payout(state.amount_list[index], &reward_mints, sol_vault_balance(jackpot_vault)?)?;
+        sol_amount = payout.sol_amount;
+        mint_amount_list = payout.amount_list;
+        flags |= LATEST_FLAG_JACKPOT;
+        jackpot_payout = Some(payout);
+    } else if token_type == 2 {
+        sol_amount = state.amount_list[index];
+    }
+
+    let item_mint_list = reward_mints.item_mint_list;
+    for (mint, amount) in item_mint_list.iter().zip(mint_amount_list).take(reward_mints.count as usize) {
+        if token_type == TOKEN_TYPE_JACKPOT && amount == 0 {
+            continue;
+        }
+        user_pendingstate.add_item(*mint, amount)?;
+        liabilities.add(mint, amount)?;
+    }
+
+    let is_sol = token_type == 2 || sol_amount > 0;
+    if is_sol {
+        user_pendingstate.is_sol = true;
+        user_pendingstate.sol_amount = user_pendingstate.sol_amount.checked_add(sol_amount).unwrap();
+        liabilities.add_sol(sol_amount)?;
+    }
+
+    let mut one_rmint = Pubkey::default();
+    // a jackpot item's amount is its payout share, record what the jackpot actually paid
+    let mut reward_amount = if is_sol || token_type == TOKEN_TYPE_JACKPOT { sol_amount } else { state.amount_list[index] };
+    if reward_mints.count > 0 {
+        one_rmint = reward_mints.item_mint_list[0];
+        if !is_sol {
+            reward_amount = mint_amount_list[0];
+        }
+    }
+
+    last_users.push_front_last_user(user, pay_amount, reward_amount, one_rmint, token_type, flags)?;
+
+    Ok(jackpot_payout)
+}
+
+// Moves a jackpot win from the jackpot vault and its ATAs into the pool vaults,
+// where it is claimed like any other reward. `token_accounts` holds the jackpot
+// and pool ATAs of every mint paid out.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_jackpot<'info>(
+    payout: &JackpotPayout,
+    pool: &Pubkey,
+    wheel_id: u64,
+    jackpot_bump: u8,
+    jackpot_vault: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    token_accounts: &[AccountInfo<'info>],
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let wheel_id_bytes = wheel_id.to_le_bytes();
+    let signer_seeds = &[JACKPOT_VAULT_SEED, &wheel_id_bytes[..], &[jackpot_bump]];
+
+    if payout.sol_amount > 0 {
+        invoke_signed(
+            &system_instruction::transfer(&jackpot_vault.key(), &vault.key(), payout.sol_amount),
+            &[jackpot_vault.clone(), vault.clone(), system_program.clone()],
+            &[&signer_seeds[..]],
+        )?;
+    }
+
+    for i in 0..payout.count as usize {
+        let amount = payout.amount_list[i];
+        if amount == 0 {
+            continue;
+        }
+        let find_ata = |owner: &Pubkey| {
+            let key = get_associated_token_address(owner, &payout.mint_list[i]);
+            token_accounts
+                .iter()
+                .find(|account| account.key() == key)
+                .ok_or(error!(SpinError::IncorrectRewardVault))
+        };
+
+        let cpi_ctx = CpiContext::new(
+            token_program.clone(),
+            anchor_spl::token::Transfer {
+                from: find_ata(&jackpot_vault.key())?.clone(),
+                to: find_ata(pool)?.clone(),
+                authority: jackpot_vault.clone(),
+            },
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(&[&signer_seeds[..]]), amount)?;
+    }
+
+    Ok(())
+}
+
+// Checks that `ix` is an ed25519 program instruction verifying exactly one
+// signature of `message` by `pubkey`, with all data inline in that instruction.
+pub fn verify_ed25519_ix(ix: &Instruction, pubkey: &Pubkey, message: &[u8], signature: &[u8; 64]) -> Result<()> {
+    require!(ix.program_id == ed25519_program::id(), SpinError::InvalidOracleProof);
+    require!(ix.accounts.is_empty(), SpinError::InvalidOracleProof);
+
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, SpinError::InvalidOracleProof);
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let signature_offset = read_u16(2) as usize;
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    require!(
+        signature_ix_index == u16::MAX && public_key_ix_index == u16::MAX && message_ix_index == u16::MAX,
+        SpinError::InvalidOracleProof
+    );
+    require!(
+        data.len() >= signature_offset + 64
+            && data.len() >= public_key_offset + 32
+            && data.len() >= message_offset + message_size,
+        SpinError::InvalidOracleProof
+    );
+
+    require!(&data[public_key_offset..public_key_offset + 32] == pubkey.as_ref(), SpinError::InvalidOracleProof);
+    require!(&data[signature_offset..signature_offset + 64] == signature.as_ref(), SpinError::InvalidOracleProof);
+    require!(&data[message_offset..message_offset + message_size] == message, SpinError::InvalidOracleProof);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SlotHashes layout: entry count, then (slot, hash) pairs newest first
+    fn slot_hash_of(slots: &[u64], target_slot: u64) -> Result<[u8; 32]> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*slot as u8; 32]);
+        }
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &key, false, 0);
+        get_slot_hash(&info, target_slot)
+    }
+
+    #[test]
+    fn slot_hash_takes_the_target_slot() {
+        assert_eq!(slot_hash_of(&[12, 11, 10, 9], 10).unwrap(), [10; 32]);
+    }
+
+    #[test]
+    fn slot_hash_moves_past_skipped_slots() {
+        assert_eq!(slot_hash_of(&[12, 11, 9], 10).unwrap(), [11; 32]);
+    }
+
+    #[test]
+    fn slot_hash_waits_for_the_target_slot() {
+        assert_spin_err(slot_hash_of(&[9, 8], 10), SpinError::SpinNotReady);
+    }
+
+    #[test]
+    fn slot_hash_refuses_evicted_slots() {
+        assert_spin_err(slot_hash_of(&[600, 599], 10), SpinError::SpinRequestExpired);
+    }
+
+    #[test]
+    fn mint_amount_scales_to_the_mint_decimals() {
+        assert_eq!(to_mint_amount(1_000_000_000, 6).unwrap(), 1_000_000);
+        assert_eq!(to_mint_amount(5, REWARD_TOKEN_DECIMAL).unwrap(), 5);
+        assert_eq!(to_mint_amount(1, 12).unwrap(), 1000);
+    }
+
+    #[test]
+    fn mint_amount_refuses_overflows() {
+        assert_spin_err(to_mint_amount(u64::MAX, 10), SpinError::AmountOverflow);
+        assert_spin_err(to_mint_amount(1, 30), SpinError::AmountOverflow);
+    }
+
+    fn token_account(mint: Pubkey, amount: u64) -> TokenAccount {
+        use solana_program::program_pack::Pack;
+
+        let account = spl_token::state::Account {
+            mint,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(account, &mut data).unwrap();
+        TokenAccount::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn token_reserves_keep_liabilities_covered() {
+        let (mint, other_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut liabilities = Liabilities {
+            sol_amount: 0,
+            mint_list: [Pubkey::default(); MAX_REWARD_TOKEN_COUNT],
+            amount_list: [0; MAX_REWARD_TOKEN_COUNT],
+            count: 0,
+        };
+        liabilities.add(&mint, 60).unwrap();
+        liabilities.add(&other_mint, 1000).unwrap();
+
+        let source = token_account(mint, 100);
+        require_token_reserves(&source, &liabilities, 40).unwrap();
+        assert_spin_err(require_token_reserves(&source, &liabilities, 41), SpinError::InsufficientReserves);
+        assert_spin_err(require_token_reserves(&source, &liabilities, 101), SpinError::InsufficientReserves);
+    }
+
+    // ed25519 program layout: one signature, offsets header, then pubkey, signature, message
+    fn ed25519_ix(pubkey: &Pubkey, signature: &[u8; 64], message: &[u8], ix_index: u16) -> Instruction {
+        let (public_key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            ix_index,
+            public_key_offset,
+            ix_index,
+            message_offset,
+            message.len() as u16,
+            ix_index,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        Instruction { program_id: ed25519_program::id(), accounts: vec![], data }
+    }
+
+    #[test]
+    fn ed25519_ix_must_verify_the_oracle_signing_the_request() {
+        let (oracle, signature, message) = (Pubkey::new_unique(), [7; 64], [3; 40]);
+        verify_ed25519_ix(&ed25519_ix(&oracle, &signature, &message, u16::MAX), &oracle, &message, &signature).unwrap();
+
+        let ix = ed25519_ix(&Pubkey::new_unique(), &signature, &message, u16::MAX);
+        assert_spin_err(verify_ed25519_ix(&ix, &oracle, &message, &signature), SpinError::InvalidOracleProof);
+
+        let ix = ed25519_ix(&oracle, &signature, &[4; 40], u16::MAX);
+        assert_spin_err(verify_ed25519_ix(&ix, &oracle, &message, &signature), SpinError::InvalidOracleProof);
+
+        let ix = ed25519_ix(&oracle, &[8; 64], &message, u16::MAX);
+        assert_spin_err(verify_ed25519_ix(&ix, &oracle, &message, &signature), SpinError::InvalidOracleProof);
+    }
+
+    #[test]
+    fn ed25519_ix_must_keep_its_data_inline() {
+        let (oracle, signature, message) = (Pubkey::new_unique(), [7; 64], [3; 40]);
+        // offsets into another instruction would verify bytes this check never sees
+        let ix = ed25519_ix(&oracle, &signature, &message, 0);
+        assert_spin_err(verify_ed25519_ix(&ix, &oracle, &message, &signature), SpinError::InvalidOracleProof);
+
+        let mut ix = ed25519_ix(&oracle, &signature, &message, u16::MAX);
+        ix.data[8..10].copy_from_slice(&1u16.to_le_bytes());
+        assert_spin_err(verify_ed25519_ix(&ix, &oracle, &message, &signature), SpinError::InvalidOracleProof);
+    }
+}
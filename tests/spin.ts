@@ -1,184 +1,798 @@
 import * as anchor from "@project-serum/anchor";
-import { Program } from "@project-serum/anchor";
+import { Program, BN } from "@project-serum/anchor";
 import { Spin } from "../target/types/spin";
 
-import { PublicKey, SystemProgram, Transaction, Connection, Commitment, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, createAccount, mintTo } from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram, Transaction, SYSVAR_RENT_PUBKEY, SYSVAR_SLOT_HASHES_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, getAccount } from "@solana/spl-token";
 import { assert } from "chai";
-import { connection } from "@iboss/terrain/lib/config";
+import { createHash } from "crypto";
+
+const LAMPORTS_PER_SOL = anchor.web3.LAMPORTS_PER_SOL;
+const RATIO_TOTAL = 100000;
+const TOKEN_TYPE_SOL = 2;
+// SOL items carry no per-mint rewards
+const NO_MINTS = new Array(10).fill(PublicKey.default);
+const NO_AMOUNTS = new Array(10).fill(new BN(0));
+
+type WheelItem = { ratio: number; amount: number };
 
 describe("spin", () => {
-  // Configure the client to use the local cluster.
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
   const program = anchor.workspace.Spin as Program<Spin>;
-
-  let mintA = null;
-  let mintB = null;
-  let initializerTokenAccountA = null;
-  let initializerTokenAccountB = null;
-  let takerTokenAccountA = null;
-  let takerTokenAccountB = null;
-  let vault_account_pda = null;
-  let vault_account_bump = null;
-  let vault_authority_pda = null;
-
-  let pool_account_pda = null;
-  let pool_account_bump = null;
-
-  let token_vault_list = [];
-
-  let testPoolAcc = null;
-
-  const takerAmount = 1000;
-  const initializerAmount = 500;
-
-  const escrowAccount = anchor.web3.Keypair.generate();
-  const payer = anchor.web3.Keypair.generate();
-  const mintAuthority = anchor.web3.Keypair.generate();
-  const initializerMainAccount = anchor.web3.Keypair.generate();
-  const takerMainAccount = anchor.web3.Keypair.generate();
-
-  it("Is initialized!", async () => {
-    // Airdropping tokens to a payer.
-    await provider.connection.confirmTransaction(
-      await provider.connection.requestAirdrop(payer.publicKey, 1000000000),
-      "processed"
-    );
+  const superadmin = provider.wallet.publicKey;
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+  const u64 = (n: number) => new BN(n).toArrayLike(Buffer, "le", 8);
+
+  async function pda(...seeds: Buffer[]): Promise<PublicKey> {
+    const [address] = await PublicKey.findProgramAddress(seeds, program.programId);
+    return address;
+  }
+
+  async function wheelAccounts(wheelId: number) {
+    const id = u64(wheelId);
+    return {
+      pool: await pda(Buffer.from("sw_game_vault_auth"), id),
+      lastUsers: await pda(Buffer.from("LAST_USERS_SEED"), id),
+      state: await pda(Buffer.from("SPIN_ITEMS_SEED"), id),
+      freeState: await pda(Buffer.from("FREE_SPIN_ITEMS_SEED"), id),
+      adminInfo: await pda(Buffer.from("ADMIN_LIST_SEED"), id),
+      payTokenList: await pda(Buffer.from("PAY_TOKEN_LIST_SEED"), id),
+      treasury: await pda(Buffer.from("TREASURY_SEED"), id),
+      multisig: await pda(Buffer.from("MULTISIG_SEED"), id),
+      pendingConfig: await pda(Buffer.from("PENDING_CONFIG_SEED"), id),
+      liabilities: await pda(Buffer.from("LIABILITIES_SEED"), id),
+      jackpot: await pda(Buffer.from("JACKPOT_SEED"), id),
+      jackpotVault: await pda(Buffer.from("JACKPOT_VAULT_SEED"), id),
+      vault: await pda(Buffer.from("SOL_VAULT"), id),
+      ticketMint: await pda(Buffer.from("TICKET_MINT_SEED"), id),
+    };
+  }
+
+  async function spinAccounts(wheelId: number, round: number, user: PublicKey) {
+    return {
+      userState: await pda(Buffer.from("USER_STATE_SEED"), user.toBuffer()),
+      spinRequest: await pda(Buffer.from("SPIN_REQUEST_SEED"), u64(wheelId), u64(round), user.toBuffer()),
+      userPendingstate: await pda(Buffer.from("PENDING_CLAIM_SEED"), u64(wheelId), u64(round), user.toBuffer()),
+    };
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+    } catch (e) {
+      assert.include(String(e) + JSON.stringify(e.logs ?? []), code);
+      return;
+    }
+    assert.fail(`expected ${code}`);
+  }
+
+  async function newPlayer(sol: number): Promise<Keypair> {
+    const player = Keypair.generate();
     await provider.connection.confirmTransaction(
-      await provider.connection.requestAirdrop(provider.wallet.publicKey, 1000000000),
-      "processed"
+      await provider.connection.requestAirdrop(player.publicKey, sol * LAMPORTS_PER_SOL),
+      "confirmed"
     );
-
-    // Fund Main Accounts
-    await provider.sendAndConfirm(
-      (() => {
-        const tx = new Transaction();
-        tx.add(
-          SystemProgram.transfer({
-            fromPubkey: payer.publicKey,
-            toPubkey: initializerMainAccount.publicKey,
-            lamports: 100000000,
-          }),
-          SystemProgram.transfer({
-            fromPubkey: payer.publicKey,
-            toPubkey: takerMainAccount.publicKey,
-            lamports: 100000000,
+    return player;
+  }
+
+  // spins that pay nothing into the vault need it funded up front
+  async function fundVault(wheelId: number, lamports: number) {
+    const { vault } = await wheelAccounts(wheelId);
+    await provider.sendAndConfirm(new Transaction().add(SystemProgram.transfer({ fromPubkey: superadmin, toPubkey: vault, lamports })));
+  }
+
+  // A wheel paying SOL only, without revenue split so spins need no remaining accounts.
+  async function createWheel(wheelId: number, solPrice: number, items: WheelItem[]) {
+    const wheel = await wheelAccounts(wheelId);
+
+    await program.methods
+      .initialize(new BN(wheelId))
+      .accounts({
+        initializer: superadmin,
+        pool: wheel.pool,
+        lastUsers: wheel.lastUsers,
+        superAdmin: superadmin,
+        state: wheel.state,
+        adminInfo: wheel.adminInfo,
+        payTokenList: wheel.payTokenList,
+        treasury: wheel.treasury,
+        multisig: wheel.multisig,
+        pendingConfig: wheel.pendingConfig,
+        liabilities: wheel.liabilities,
+        jackpot: wheel.jackpot,
+        jackpotVault: wheel.jackpotVault,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .setRevenueSplit(new BN(wheelId), new BN(0), [], [])
+      .accounts({ superadmin, pool: wheel.pool, multisig: wheel.multisig })
+      .rpc();
+
+    await program.methods
+      .setPayInfo(new BN(wheelId), new BN(solPrice))
+      .accounts({
+        admin: superadmin,
+        pool: wheel.pool,
+        multisig: wheel.multisig,
+        adminInfo: wheel.adminInfo,
+        state: wheel.state,
+        payTokenList: wheel.payTokenList,
+      })
+      .rpc();
+
+    for (const item of items) {
+      await addItem(wheelId, item);
+    }
+
+    return wheel;
+  }
+
+  async function addItem(wheelId: number, item: WheelItem) {
+    const wheel = await wheelAccounts(wheelId);
+    return program.methods
+      .addItem(new BN(wheelId), NO_MINTS, 0, NO_AMOUNTS, TOKEN_TYPE_SOL, item.ratio, new BN(item.amount))
+      .accounts({
+        admin: superadmin,
+        pool: wheel.pool,
+        multisig: wheel.multisig,
+        adminInfo: wheel.adminInfo,
+        state: wheel.state,
+        payTokenList: wheel.payTokenList,
+      })
+      .rpc();
+  }
+
+  function newSeed(): { seed: number[]; commitment: number[] } {
+    const seed = Keypair.generate().publicKey.toBuffer();
+    return { seed: Array.from(seed), commitment: Array.from(createHash("sha256").update(seed).digest()) };
+  }
+
+  async function requestSpinSol(wheelId: number, player: Keypair, round: number, commitment: number[]) {
+    const wheel = await wheelAccounts(wheelId);
+    const spin = await spinAccounts(wheelId, round, player.publicKey);
+    await program.methods
+      .requestSpinSol(new BN(wheelId), commitment, new BN(round), false)
+      .accounts({
+        user: player.publicKey,
+        pool: wheel.pool,
+        userState: spin.userState,
+        spinRequest: spin.spinRequest,
+        vault: wheel.vault,
+        jackpot: wheel.jackpot,
+        jackpotVault: wheel.jackpotVault,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  // the settle slot's hash only exists once the slot after it was produced
+  async function waitForSettleSlot(wheelId: number, player: Keypair, round: number) {
+    const spin = await spinAccounts(wheelId, round, player.publicKey);
+    const request = await program.account.spinRequest.fetch(spin.spinRequest);
+    while ((await provider.connection.getSlot()) <= request.targetSlot.toNumber() + 1) {
+      await sleep(400);
+    }
+  }
+
+  async function settleSpin(wheelId: number, player: Keypair, round: number, seed: number[], isFree = false) {
+    const wheel = await wheelAccounts(wheelId);
+    const spin = await spinAccounts(wheelId, round, player.publicKey);
+    await program.methods
+      .settleSpin(new BN(wheelId), new BN(round), seed)
+      .accounts({
+        user: player.publicKey,
+        pool: wheel.pool,
+        lastUsers: wheel.lastUsers,
+        state: isFree ? wheel.freeState : wheel.state,
+        liabilities: wheel.liabilities,
+        vault: wheel.vault,
+        jackpot: wheel.jackpot,
+        jackpotVault: wheel.jackpotVault,
+        spinRequest: spin.spinRequest,
+        userPendingstate: spin.userPendingstate,
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  async function playSpinSol(wheelId: number, player: Keypair, round: number) {
+    const { seed, commitment } = newSeed();
+    await requestSpinSol(wheelId, player, round, commitment);
+    await waitForSettleSlot(wheelId, player, round);
+    await settleSpin(wheelId, player, round, seed);
+  }
+
+  async function claimAll(wheelId: number, player: Keypair, round: number) {
+    const wheel = await wheelAccounts(wheelId);
+    const spin = await spinAccounts(wheelId, round, player.publicKey);
+    await program.methods
+      .claimAll(new BN(wheelId))
+      .accounts({
+        owner: player.publicKey,
+        pool: wheel.pool,
+        liabilities: wheel.liabilities,
+        vault: wheel.vault,
+        userPendingstate: spin.userPendingstate,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  describe("commit-reveal spins", () => {
+    const wheelId = 1;
+    const solPrice = LAMPORTS_PER_SOL / 10;
+    const reward = LAMPORTS_PER_SOL / 20;
+    let player: Keypair;
+
+    before(async () => {
+      await createWheel(wheelId, solPrice, [{ ratio: RATIO_TOTAL, amount: reward }]);
+      player = await newPlayer(2);
+    });
+
+    it("settles a spin against its commitment and records the reward", async () => {
+      const { seed, commitment } = newSeed();
+      await requestSpinSol(wheelId, player, 1, commitment);
+      await waitForSettleSlot(wheelId, player, 1);
+      await settleSpin(wheelId, player, 1, seed);
+
+      const spin = await spinAccounts(wheelId, 1, player.publicKey);
+      const pending = await program.account.userPendingClaimState.fetch(spin.userPendingstate);
+      assert.isTrue(pending.isSol);
+      assert.equal(pending.solAmount.toNumber(), reward);
+      assert.isNull(await provider.connection.getAccountInfo(spin.spinRequest));
+
+      const liabilities = await program.account.liabilities.fetch((await wheelAccounts(wheelId)).liabilities);
+      assert.equal(liabilities.solAmount.toNumber(), reward);
+    });
+
+    it("pays the reward out of the vault on claim", async () => {
+      const before = await provider.connection.getBalance(player.publicKey);
+      await claimAll(wheelId, player, 1);
+
+      assert.isAbove(await provider.connection.getBalance(player.publicKey), before);
+      const liabilities = await program.account.liabilities.fetch((await wheelAccounts(wheelId)).liabilities);
+      assert.equal(liabilities.solAmount.toNumber(), 0);
+    });
+
+    it("refuses a seed that doesn't match the commitment", async () => {
+      const { seed, commitment } = newSeed();
+      await requestSpinSol(wheelId, player, 2, commitment);
+      await waitForSettleSlot(wheelId, player, 2);
+
+      await expectError(settleSpin(wheelId, player, 2, newSeed().seed), "InvalidSpinCommitment");
+      await settleSpin(wheelId, player, 2, seed);
+    });
+
+    it("refuses an expiry refund while the spin can still be settled", async () => {
+      const wheel = await wheelAccounts(wheelId);
+      const { commitment } = newSeed();
+      await requestSpinSol(wheelId, player, 3, commitment);
+
+      const spin = await spinAccounts(wheelId, 3, player.publicKey);
+      await expectError(
+        program.methods
+          .closeExpiredSpin(new BN(wheelId), new BN(3))
+          .accounts({
+            user: player.publicKey,
+            pool: wheel.pool,
+            userState: spin.userState,
+            liabilities: wheel.liabilities,
+            spinRequest: spin.spinRequest,
+            randomnessRequest: await pda(Buffer.from("RANDOMNESS_REQUEST_SEED"), spin.spinRequest.toBuffer()),
           })
-        );
-        return tx;
-      })(),
-      [payer]
-    );
-
-    mintA = await createMint(
-      provider.connection,
-      payer,
-      mintAuthority.publicKey,
-      null,
-      0
-    );
-
-
-    let ss = new PublicKey(initializerMainAccount.publicKey);
-    console.log('1111111111111', mintA, ss);
-
-    initializerTokenAccountA = await createAccount(provider.connection, ss, mintA);
-    takerTokenAccountA = await createAccount(provider.connection, takerMainAccount.publicKey, mintA);
-
-
-    await mintTo(
-      connection,
-      initializerTokenAccountA,
-      mintAuthority.publicKey,
-      [mintAuthority],
-      initializerAmount
-    );
-
-    await mintTo(
-      connection,
-      takerTokenAccountB,
-      mintAuthority.publicKey,
-      [mintAuthority],
-      takerAmount
-    );
+          .signers([player])
+          .rpc(),
+        "SpinNotExpired"
+      );
+    });
   });
 
+  describe("wheel odds", () => {
+    const wheelId = 4;
+    const solPrice = LAMPORTS_PER_SOL / 10;
 
-  it("Initialize escrow", async () => {
-    let [_pool, _pool_bump] = await PublicKey.findProgramAddress([Buffer.from(anchor.utils.bytes.utf8.encode("sw_game_seeds"))], program.programId);
-    pool_account_pda = _pool;
-    pool_account_bump = _pool_bump;
-
-    const [_vault_account_pda, _vault_account_bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(anchor.utils.bytes.utf8.encode("sw_token-seed"))],
-      program.programId
-    );
-    vault_account_pda = _vault_account_pda;
-    vault_account_bump = _vault_account_bump;
-
-    const [_vault_authority_pda, _vault_authority_bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(anchor.utils.bytes.utf8.encode("escrow"))],
-      program.programId
-    );
-    vault_authority_pda = _vault_authority_pda;
-
-    console.log('initialize start...');
+    before(async () => {
+      await createWheel(wheelId, solPrice, []);
+    });
 
+    it("refuses items that push the return above the limit", async () => {
+      await expectError(addItem(wheelId, { ratio: RATIO_TOTAL, amount: solPrice * 2 }), "RtpTooHigh");
+      await addItem(wheelId, { ratio: RATIO_TOTAL, amount: solPrice });
+    });
 
-    // let randomPubkey = anchor.web3.Keypair.generate().publicKey;
-    // let [_pool111, _bump111] = await PublicKey.findProgramAddress(
-    //   [randomPubkey.toBuffer()],
-    //   program.programId
-    // );
-
-
-    const [_pool111, _bump111] = await PublicKey.findProgramAddress([Buffer.from(anchor.utils.bytes.utf8.encode("sw_game_vault_auth"))], program.programId);
-
-    vault_account_pda = _pool111;
+    it("refuses weights above the wheel total", async () => {
+      await expectError(addItem(wheelId, { ratio: 1, amount: 0 }), "SpinWeightsOverflow");
+    });
+  });
 
-    pool_account_pda = await PublicKey.createWithSeed(
-      initializerMainAccount.publicKey,
-      "user-lottery-pool",
-      program.programId,
-    );
+  describe("claim ledger", () => {
+    const wheelId = 11;
+    let player: Keypair;
+
+    before(async () => {
+      await createWheel(wheelId, LAMPORTS_PER_SOL / 10, [{ ratio: RATIO_TOTAL, amount: LAMPORTS_PER_SOL / 20 }]);
+      player = await newPlayer(1);
+      await playSpinSol(wheelId, player, 1);
+    });
+
+    it("pays a reward only once", async () => {
+      await claimAll(wheelId, player, 1);
+      await expectError(claimAll(wheelId, player, 1), "AccountNotInitialized");
+    });
+
+    it("refuses claims on someone else's rewards", async () => {
+      const thief = await newPlayer(1);
+      await playSpinSol(wheelId, player, 2);
+
+      const wheel = await wheelAccounts(wheelId);
+      const spin = await spinAccounts(wheelId, 2, player.publicKey);
+      await expectError(
+        program.methods
+          .claimAll(new BN(wheelId))
+          .accounts({
+            owner: thief.publicKey,
+            pool: wheel.pool,
+            liabilities: wheel.liabilities,
+            vault: wheel.vault,
+            userPendingstate: spin.userPendingstate,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([thief])
+          .rpc(),
+        "ConstraintRaw"
+      );
+    });
+  });
 
-    await program.rpc.initialize(
-      _bump111,
-      {
-        accounts: {
-          initializer: initializerMainAccount.publicKey,
-          pool: _pool111,
-          state: pool_account_pda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        },
-        signers: [initializerMainAccount],
-        preInstructions: [
-          SystemProgram.createAccountWithSeed({
-            fromPubkey: initializerMainAccount.publicKey,
-            basePubkey: initializerMainAccount.publicKey,
-            seed: "user-lottery-pool",
-            newAccountPubkey: pool_account_pda,
-            lamports: await provider.connection.getMinimumBalanceForRentExemption(4975),
-            space: 4975,
-            programId: program.programId,
+  describe("multisig", () => {
+    const wheelId = 14;
+    const cosigners = [Keypair.generate(), Keypair.generate()];
+    let wheel: Awaited<ReturnType<typeof wheelAccounts>>;
+
+    const proposal = (proposalId: number) => pda(Buffer.from("PROPOSAL_SEED"), u64(wheelId), u64(proposalId));
+
+    async function approve(proposalId: number, signer: Keypair) {
+      await program.methods
+        .approveProposal(new BN(wheelId), new BN(proposalId))
+        .accounts({ signer: signer.publicKey, multisig: wheel.multisig, proposal: await proposal(proposalId) })
+        .signers([signer])
+        .rpc();
+    }
+
+    async function execute(proposalId: number) {
+      await program.methods
+        .executeConfigProposal(new BN(wheelId), new BN(proposalId))
+        .accounts({
+          pool: wheel.pool,
+          multisig: wheel.multisig,
+          proposal: await proposal(proposalId),
+          proposer: superadmin,
+          state: wheel.state,
+          payTokenList: wheel.payTokenList,
+          adminInfo: wheel.adminInfo,
+          pendingConfig: wheel.pendingConfig,
+          treasury: wheel.treasury,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      wheel = await createWheel(wheelId, LAMPORTS_PER_SOL / 10, [{ ratio: RATIO_TOTAL, amount: LAMPORTS_PER_SOL / 20 }]);
+      await program.methods
+        .setMultisig(new BN(wheelId), [superadmin, ...cosigners.map((signer) => signer.publicKey)], 2, true)
+        .accounts({ authority: superadmin, pool: wheel.pool, multisig: wheel.multisig })
+        .rpc();
+    });
+
+    it("routes sensitive changes through proposals", async () => {
+      await expectError(
+        program.methods
+          .setPayInfo(new BN(wheelId), new BN(LAMPORTS_PER_SOL))
+          .accounts({
+            admin: superadmin,
+            pool: wheel.pool,
+            multisig: wheel.multisig,
+            adminInfo: wheel.adminInfo,
+            state: wheel.state,
+            payTokenList: wheel.payTokenList,
           })
-        ]
+          .rpc(),
+        "MultisigRequired"
+      );
+    });
+
+    it("executes a proposal once the threshold is met", async () => {
+      await program.methods
+        .createProposal(new BN(wheelId), new BN(0), { setPayInfo: { solPrice: new BN(LAMPORTS_PER_SOL / 5) } })
+        .accounts({ proposer: superadmin, pool: wheel.pool, multisig: wheel.multisig, proposal: await proposal(0), systemProgram: SystemProgram.programId })
+        .rpc();
+
+      await expectError(execute(0), "ProposalThresholdNotMet");
+      await approve(0, cosigners[0]);
+      await expectError(approve(0, cosigners[0]), "ProposalAlreadyApproved");
+      await execute(0);
+
+      const pool = await program.account.pool.fetch(wheel.pool);
+      assert.equal(pool.solPrice.toNumber(), LAMPORTS_PER_SOL / 5);
+    });
+
+    it("refuses approvals from outside the signer set", async () => {
+      await program.methods
+        .createProposal(new BN(wheelId), new BN(1), { setPayInfo: { solPrice: new BN(LAMPORTS_PER_SOL) } })
+        .accounts({ proposer: superadmin, pool: wheel.pool, multisig: wheel.multisig, proposal: await proposal(1), systemProgram: SystemProgram.programId })
+        .rpc();
+
+      await expectError(approve(1, Keypair.generate()), "IncorrectMultisigSigner");
+    });
+
+    it("routes oracle changes through proposals", async () => {
+      const oracle = Keypair.generate().publicKey;
+      await expectError(
+        program.methods
+          .addOracle(new BN(wheelId))
+          .accounts({ authority: superadmin, pool: wheel.pool, multisig: wheel.multisig, oracle })
+          .rpc(),
+        "MultisigRequired"
+      );
+
+      await program.methods
+        .createProposal(new BN(wheelId), new BN(2), { addOracle: { oracle } })
+        .accounts({ proposer: superadmin, pool: wheel.pool, multisig: wheel.multisig, proposal: await proposal(2), systemProgram: SystemProgram.programId })
+        .rpc();
+      await approve(2, cosigners[1]);
+      await execute(2);
+
+      const pool = await program.account.pool.fetch(wheel.pool);
+      assert.isTrue(pool.oracleList.slice(0, pool.oracleCount).some((key: PublicKey) => key.equals(oracle)));
+    });
+  });
+
+  describe("timelock", () => {
+    const wheelId = 15;
+    const solPrice = LAMPORTS_PER_SOL / 10;
+    let wheel: Awaited<ReturnType<typeof wheelAccounts>>;
+
+    const activate = () =>
+      program.methods
+        .activateConfig(new BN(wheelId))
+        .accounts({ pool: wheel.pool, state: wheel.state, payTokenList: wheel.payTokenList, pendingConfig: wheel.pendingConfig })
+        .rpc();
+
+    const setConfigDelay = (configDelay: number) =>
+      program.methods
+        .setConfigDelay(new BN(wheelId), new BN(configDelay))
+        .accounts({
+          superadmin,
+          pool: wheel.pool,
+          multisig: wheel.multisig,
+          state: wheel.state,
+          payTokenList: wheel.payTokenList,
+          pendingConfig: wheel.pendingConfig,
+        })
+        .rpc();
+
+    async function waitForActivation() {
+      const { activatesAt } = await program.account.pendingWheelConfig.fetch(wheel.pendingConfig);
+      while ((await provider.connection.getBlockTime(await provider.connection.getSlot())) <= activatesAt.toNumber()) {
+        await sleep(1000);
       }
-    );
+    }
+
+    before(async () => {
+      wheel = await createWheel(wheelId, solPrice, [{ ratio: RATIO_TOTAL, amount: solPrice / 2 }]);
+      await setConfigDelay(3);
+    });
+
+    it("refuses direct changes once a delay is set", async () => {
+      await expectError(addItem(wheelId, { ratio: 0, amount: 0 }), "ConfigTimelocked");
+    });
+
+    it("activates a staged change only after the delay", async () => {
+      await program.methods
+        .stagePayInfo(new BN(wheelId), new BN(solPrice * 2))
+        .accounts({
+          admin: superadmin,
+          pool: wheel.pool,
+          multisig: wheel.multisig,
+          adminInfo: wheel.adminInfo,
+          state: wheel.state,
+          payTokenList: wheel.payTokenList,
+          pendingConfig: wheel.pendingConfig,
+        })
+        .rpc();
+
+      await expectError(activate(), "ConfigNotReady");
+      assert.equal((await program.account.pool.fetch(wheel.pool)).solPrice.toNumber(), solPrice);
+
+      await waitForActivation();
+      await activate();
+      assert.equal((await program.account.pool.fetch(wheel.pool)).solPrice.toNumber(), solPrice * 2);
+    });
+
+    it("stages a shorter delay behind the current one", async () => {
+      await setConfigDelay(0);
+      assert.equal((await program.account.pool.fetch(wheel.pool)).configDelay.toNumber(), 3);
+
+      await waitForActivation();
+      await activate();
+      assert.equal((await program.account.pool.fetch(wheel.pool)).configDelay.toNumber(), 0);
+    });
+  });
 
-    
-    testPoolAcc = await mintA.createAccount(_pool111);
-    await mintA.mintTo(
-      testPoolAcc,
-      mintAuthority.publicKey,
-      [mintAuthority],
-      100
-    );
+  describe("liabilities breaker", () => {
+    const solPrice = LAMPORTS_PER_SOL / 10;
+    let player: Keypair;
+
+    before(async () => {
+      player = await newPlayer(3);
+    });
+
+    it("skips items the vault can't cover", async () => {
+      const wheelId = 18;
+      const wheel = await createWheel(wheelId, solPrice, [
+        { ratio: RATIO_TOTAL / 10, amount: LAMPORTS_PER_SOL },
+        { ratio: (RATIO_TOTAL * 9) / 10, amount: 0 },
+      ]);
+
+      const { seed, commitment } = newSeed();
+      const spin = await spinAccounts(wheelId, 1, player.publicKey);
+      await program.methods
+        .spinManySol(new BN(wheelId), commitment, new BN(1), false, 10)
+        .accounts({
+          user: player.publicKey,
+          pool: wheel.pool,
+          userState: spin.userState,
+          spinRequest: spin.spinRequest,
+          vault: wheel.vault,
+          jackpot: wheel.jackpot,
+          jackpotVault: wheel.jackpotVault,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([player])
+        .rpc();
+      await waitForSettleSlot(wheelId, player, 1);
+      await settleSpin(wheelId, player, 1, seed);
+
+      const pending = await program.account.userPendingClaimState.fetch(spin.userPendingstate);
+      assert.equal(pending.solAmount.toNumber(), 0);
+      const liabilities = await program.account.liabilities.fetch(wheel.liabilities);
+      assert.equal(liabilities.solAmount.toNumber(), 0);
+    });
+
+    it("refuses to settle when no item is covered", async () => {
+      const wheelId = 180;
+      await createWheel(wheelId, LAMPORTS_PER_SOL, [{ ratio: RATIO_TOTAL, amount: LAMPORTS_PER_SOL }]);
+
+      const { seed, commitment } = newSeed();
+      await requestSpinSol(wheelId, player, 1, commitment);
+      await waitForSettleSlot(wheelId, player, 1);
+      await expectError(settleSpin(wheelId, player, 1, seed), "InsufficientReserves");
+    });
+  });
 
-    console.log('initialize end...');
+  describe("credits", () => {
+    const wheelId = 23;
+    const solPrice = LAMPORTS_PER_SOL / 10;
+    let wheel: Awaited<ReturnType<typeof wheelAccounts>>;
+    let player: Keypair;
+
+    async function manageCredit(method: "depositCreditSol" | "withdrawCreditSol", amount: number) {
+      const spin = await spinAccounts(wheelId, 0, player.publicKey);
+      await program.methods[method](new BN(wheelId), new BN(amount))
+        .accounts({
+          user: player.publicKey,
+          pool: wheel.pool,
+          userState: spin.userState,
+          vault: wheel.vault,
+          liabilities: wheel.liabilities,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([player])
+        .rpc();
+    }
+
+    async function solCredit(): Promise<number> {
+      const userState = await program.account.userState.fetch((await spinAccounts(wheelId, 0, player.publicKey)).userState);
+      const index = userState.creditPoolList.findIndex((pool: PublicKey) => pool.equals(wheel.pool));
+      return index < 0 || index >= userState.creditCount ? 0 : userState.creditAmountList[index].toNumber();
+    }
+
+    before(async () => {
+      wheel = await createWheel(wheelId, solPrice, [{ ratio: RATIO_TOTAL, amount: solPrice / 2 }]);
+      player = await newPlayer(2);
+    });
+
+    it("spends deposited credits on bulk spins", async () => {
+      await manageCredit("depositCreditSol", solPrice * 5);
+      assert.equal(await solCredit(), solPrice * 5);
+
+      const { seed, commitment } = newSeed();
+      const spin = await spinAccounts(wheelId, 1, player.publicKey);
+      await program.methods
+        .spinWithCreditSol(new BN(wheelId), commitment, new BN(1), false, 2)
+        .accounts({
+          user: player.publicKey,
+          pool: wheel.pool,
+          userState: spin.userState,
+          spinRequest: spin.spinRequest,
+          vault: wheel.vault,
+          liabilities: wheel.liabilities,
+          jackpot: wheel.jackpot,
+          jackpotVault: wheel.jackpotVault,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([player])
+        .rpc();
+      assert.equal(await solCredit(), solPrice * 3);
+
+      await waitForSettleSlot(wheelId, player, 1);
+      await settleSpin(wheelId, player, 1, seed);
+      const pending = await program.account.userPendingClaimState.fetch(spin.userPendingstate);
+      assert.equal(pending.solAmount.toNumber(), solPrice);
+    });
+
+    it("withdraws no more than the credit balance", async () => {
+      await expectError(manageCredit("withdrawCreditSol", solPrice * 3 + 1), "InsufficientCredits");
+      await manageCredit("withdrawCreditSol", solPrice * 3);
+      assert.equal(await solCredit(), 0);
+    });
   });
 
+  describe("tickets", () => {
+    const wheelId = 24;
+    const reward = LAMPORTS_PER_SOL / 20;
+    let wheel: Awaited<ReturnType<typeof wheelAccounts>>;
+    let player: Keypair;
+    let ticketAccount: PublicKey;
+
+    async function requestSpinTicket(round: number, commitment: number[], spinCount: number) {
+      const spin = await spinAccounts(wheelId, round, player.publicKey);
+      await program.methods
+        .requestSpinTicket(new BN(wheelId), commitment, new BN(round), false, spinCount)
+        .accounts({
+          user: player.publicKey,
+          pool: wheel.pool,
+          userState: spin.userState,
+          spinRequest: spin.spinRequest,
+          ticketMint: wheel.ticketMint,
+          ticketAccount,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([player])
+        .rpc();
+    }
+
+    before(async () => {
+      wheel = await createWheel(wheelId, LAMPORTS_PER_SOL / 10, [{ ratio: RATIO_TOTAL, amount: reward }]);
+      await fundVault(wheelId, LAMPORTS_PER_SOL);
+      player = await newPlayer(1);
+
+      await program.methods
+        .initTicketMint(new BN(wheelId))
+        .accounts({
+          superadmin,
+          pool: wheel.pool,
+          ticketMint: wheel.ticketMint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      ticketAccount = await createAccount(provider.connection, player, wheel.ticketMint, player.publicKey);
+      await program.methods
+        .mintTickets(new BN(wheelId), new BN(3))
+        .accounts({
+          admin: superadmin,
+          pool: wheel.pool,
+          adminInfo: wheel.adminInfo,
+          multisig: wheel.multisig,
+          ticketMint: wheel.ticketMint,
+          destAccount: ticketAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    });
+
+    it("burns one ticket per spin", async () => {
+      const { seed, commitment } = newSeed();
+      await requestSpinTicket(1, commitment, 2);
+      assert.equal(Number((await getAccount(provider.connection, ticketAccount)).amount), 1);
+
+      await waitForSettleSlot(wheelId, player, 1);
+      await settleSpin(wheelId, player, 1, seed);
+      const spin = await spinAccounts(wheelId, 1, player.publicKey);
+      const pending = await program.account.userPendingClaimState.fetch(spin.userPendingstate);
+      assert.equal(pending.solAmount.toNumber(), reward * 2);
+    });
+
+    it("refuses spins beyond the ticket balance", async () => {
+      await expectError(requestSpinTicket(2, newSeed().commitment, 2), "insufficient funds");
+    });
+  });
 
+  describe("free spins", () => {
+    const wheelId = 25;
+    const reward = LAMPORTS_PER_SOL / 100;
+    let wheel: Awaited<ReturnType<typeof wheelAccounts>>;
+    let player: Keypair;
+
+    async function requestFreeSpin(round: number, commitment: number[]) {
+      const spin = await spinAccounts(wheelId, round, player.publicKey);
+      await program.methods
+        .requestFreeSpin(new BN(wheelId), commitment, new BN(round), false)
+        .accounts({
+          user: player.publicKey,
+          pool: wheel.pool,
+          userState: spin.userState,
+          spinRequest: spin.spinRequest,
+          freeState: wheel.freeState,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([player])
+        .rpc();
+    }
+
+    const setFreeSpinPeriod = (period: number) =>
+      program.methods
+        .setFreeSpinPeriod(new BN(wheelId), new BN(period))
+        .accounts({ admin: superadmin, pool: wheel.pool, multisig: wheel.multisig, adminInfo: wheel.adminInfo })
+        .rpc();
+
+    before(async () => {
+      wheel = await createWheel(wheelId, LAMPORTS_PER_SOL / 10, [{ ratio: RATIO_TOTAL, amount: LAMPORTS_PER_SOL / 20 }]);
+      await fundVault(wheelId, LAMPORTS_PER_SOL);
+      player = await newPlayer(1);
+
+      await program.methods
+        .initFreeSpinItems(new BN(wheelId))
+        .accounts({ superadmin, pool: wheel.pool, freeState: wheel.freeState, systemProgram: SystemProgram.programId })
+        .rpc();
+      await program.methods
+        .setFreeSpinItem(new BN(wheelId), 0, NO_MINTS, 0, NO_AMOUNTS, TOKEN_TYPE_SOL, RATIO_TOTAL, new BN(reward), 1)
+        .accounts({ admin: superadmin, pool: wheel.pool, multisig: wheel.multisig, adminInfo: wheel.adminInfo, freeState: wheel.freeState })
+        .rpc();
+    });
+
+    it("refuses free spins until a period is set", async () => {
+      await expectError(requestFreeSpin(1, newSeed().commitment), "FreeSpinDisabled");
+      await expectError(setFreeSpinPeriod(-1), "InvalidFreeSpinPeriod");
+    });
+
+    it("draws from the free spin items", async () => {
+      await setFreeSpinPeriod(86400);
+
+      const { seed, commitment } = newSeed();
+      await requestFreeSpin(1, commitment);
+      await waitForSettleSlot(wheelId, player, 1);
+      await settleSpin(wheelId, player, 1, seed, true);
+
+      const spin = await spinAccounts(wheelId, 1, player.publicKey);
+      const pending = await program.account.userPendingClaimState.fetch(spin.userPendingstate);
+      assert.equal(pending.solAmount.toNumber(), reward);
+    });
+
+    it("allows one free spin per period", async () => {
+      await expectError(requestFreeSpin(2, newSeed().commitment), "FreeSpinCooldown");
+    });
+  });
 });